@@ -1,22 +1,38 @@
-use super::super::semantics::semantics::interpret_file;
-use super::super::parser::parser::parse_file;
+use super::super::semantics::semantics::check_file;
 use super::super::irs::three_address::compiler::{compile_three_address};
+use super::super::irs::cfg::cfg_creator::construct_program_graph;
+use super::super::irs::verifier::verifier::verify_ir;
+use super::super::semantics::lints::LintConfig;
 
-pub fn assemble(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, debug: bool) {
-    match parse_file(input) {
-        Ok(ast) => {
-            match interpret_file(input, debug) {
-                Ok(_) => {
-                    // three address code
-                    let three_addr_code = compile_three_address(ast);
+pub fn assemble(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, lints: &LintConfig, debug: bool, verify: bool) {
+    // scans, parses and checks the file once, the IR builders share the analyzed program
+    match check_file(input, lints, debug) {
+        Ok((analyzed_program, warnings)) => {
+            for warning in warnings {
+                eprintln!("{}", warning);
+            }
+
+            // control flow graphs + three address code
+            let program_graph = construct_program_graph(&analyzed_program);
+            let three_addr_code = compile_three_address(&analyzed_program);
+
+            if verify || cfg!(debug_assertions) {
+                let verifier = verify_ir(&three_addr_code, &program_graph);
+                if debug {
+                    for warning in &verifier.warnings {
+                        eprintln!("{}", warning);
+                    }
                 }
-                Err(e) => {
-                    writeln!(writer, "Error in semantic analysis of file with the following errors reported: \n {:?}", e).unwrap();
+                if !verifier.errors.is_empty() {
+                    for error in verifier.errors {
+                        writeln!(writer, "{}", error).unwrap();
+                    }
+                    std::process::exit(1);
                 }
             }
         }
         Err(e) => {
-            writeln!(writer, "Error parsing input file with error: {:?}", e).unwrap();
+            writeln!(writer, "Error in analysis of file with the following errors reported: \n {:?}", e).unwrap();
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,738 @@
+use super::super::parser::AST;
+use super::super::parser::visitor::Visitor;
+use super::super::semantics::semantics::check_file;
+use super::super::semantics::lints::LintConfig;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// exit codes used by the decaf runtime
+const OUT_OF_BOUNDS_EXIT: i32 = -1;
+const NO_RETURN_EXIT: i32 = -2;
+const RUNTIME_ERROR_EXIT: i32 = -3;
+
+// stack size of the evaluation thread, decaf programs can recurse deeply
+const EVAL_STACK_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Value {
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    Str(String), // only string literals passed to imports
+    Void,
+}
+
+impl Value {
+    fn zero(type_name: &str) -> Value {
+        match type_name {
+            "int" => Value::Int(0),
+            "long" => Value::Long(0),
+            "bool" => Value::Bool(false),
+            _ => Value::Void,
+        }
+    }
+
+    fn as_i64(&self) -> i64 {
+        match self {
+            Value::Int(v) => *v as i64,
+            Value::Long(v) => *v,
+            Value::Bool(v) => *v as i64,
+            _ => 0,
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(v) => *v,
+            _ => self.as_i64() != 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+enum Variable {
+    Scalar(Value),
+    Array(Vec<Value>),
+}
+
+// an assignable location with its index already evaluated and bounds checked
+enum Slot {
+    Scalar(String),
+    Element(String, usize),
+}
+
+pub struct Evaluator {
+    globals: HashMap<String, Variable>,
+    frames: Vec<Vec<HashMap<String, Variable>>>, // call stack, each frame is a stack of block scopes
+    methods: HashMap<String, Rc<AST::MethodDecl>>, // shared so a call clones the handle, not the method's AST
+    output: String,
+
+    // control flow flags
+    break_flag: bool,
+    continue_flag: bool,
+    return_flag: bool,
+    exit_code: Option<i32>, // set once the program halts with a runtime error
+
+    // func result holders
+    result_value: Value,
+
+    // debug mode
+    debug: bool,
+}
+
+impl Evaluator {
+    fn halted(&self) -> bool {
+        self.exit_code.is_some()
+    }
+
+    fn interrupted(&self) -> bool {
+        self.break_flag || self.continue_flag || self.return_flag || self.halted()
+    }
+
+    fn runtime_error(&mut self, error: &str, exit_code: i32) {
+        if !self.halted() {
+            eprintln!("Runtime Error: {}", error);
+            self.exit_code = Some(exit_code);
+        }
+    }
+
+    fn declare_var(&mut self, name: &str, var: Variable) {
+        match self.frames.last_mut() {
+            Some(frame) => {
+                frame.last_mut().unwrap().insert(name.to_string(), var);
+            },
+            None => {
+                self.globals.insert(name.to_string(), var);
+            },
+        }
+    }
+
+    fn lookup_var(&mut self, name: &str) -> &mut Variable {
+        if let Some(frame) = self.frames.last_mut() {
+            for scope in frame.iter_mut().rev() {
+                if let Some(var) = scope.get_mut(name) {
+                    return var;
+                }
+            }
+        }
+        self.globals.get_mut(name).expect("identifier should be declared after semantic checks")
+    }
+
+    fn eval(&mut self, expr: &AST::ASTNode) -> Value {
+        self.result_value = Value::Void;
+        expr.accept(self);
+        std::mem::replace(&mut self.result_value, Value::Void)
+    }
+
+    // evaluate the index of an index expression and check it against the array bounds
+    fn eval_array_index(&mut self, index_expression: &AST::IndexExpression) -> Option<usize> {
+        let ind = self.eval(&index_expression.idx_expr).as_i64();
+        let array_name = index_expression.id.name.as_str();
+        let array_len = match self.lookup_var(array_name) {
+            Variable::Array(values) => values.len(),
+            Variable::Scalar(_) => 0,
+        };
+        if ind < 0 || ind as usize >= array_len {
+            self.runtime_error(&format!("Line {} - array index {} out of bounds for {} of length {}.",
+                               index_expression.id.line_num, ind, array_name, array_len), OUT_OF_BOUNDS_EXIT);
+            return None;
+        }
+        Some(ind as usize)
+    }
+
+    // evaluates the index of a location once, so compound assignments read and write the same element
+    fn resolve_location(&mut self, location: &AST::ASTNode) -> Option<Slot> {
+        match location {
+            AST::ASTNode::Identifier(identifier) => Some(Slot::Scalar(identifier.name.clone())),
+            AST::ASTNode::IndexExpression(index_expression) => {
+                let ind = self.eval_array_index(index_expression)?;
+                Some(Slot::Element(index_expression.id.name.clone(), ind))
+            },
+            _ => {
+                self.runtime_error("invalid location in assignment.", RUNTIME_ERROR_EXIT);
+                None
+            },
+        }
+    }
+
+    fn read_location(&mut self, slot: &Slot) -> Value {
+        match slot {
+            Slot::Scalar(name) => match self.lookup_var(name) {
+                Variable::Scalar(value) => value.clone(),
+                Variable::Array(_) => Value::Void,
+            },
+            Slot::Element(name, ind) => match self.lookup_var(name) {
+                Variable::Array(values) => values[*ind].clone(),
+                Variable::Scalar(_) => Value::Void,
+            },
+        }
+    }
+
+    fn write_location(&mut self, slot: &Slot, value: Value) {
+        match slot {
+            Slot::Scalar(name) => {
+                *self.lookup_var(name) = Variable::Scalar(value);
+            },
+            Slot::Element(name, ind) => {
+                if let Variable::Array(values) = self.lookup_var(name) {
+                    values[*ind] = value;
+                }
+            },
+        }
+    }
+
+    fn arith(&mut self, op: &str, left: Value, right: Value) -> Value {
+        if (op == "/" || op == "%") && right.as_i64() == 0 {
+            self.runtime_error("division by zero.", RUNTIME_ERROR_EXIT);
+            return left;
+        }
+        match (left, right) {
+            (Value::Int(l), Value::Int(r)) => Value::Int(match op {
+                "+" => l.wrapping_add(r),
+                "-" => l.wrapping_sub(r),
+                "*" => l.wrapping_mul(r),
+                "/" => l.wrapping_div(r),
+                _ => l.wrapping_rem(r),
+            }),
+            (l, r) => {
+                let (l, r) = (l.as_i64(), r.as_i64());
+                Value::Long(match op {
+                    "+" => l.wrapping_add(r),
+                    "-" => l.wrapping_sub(r),
+                    "*" => l.wrapping_mul(r),
+                    "/" => l.wrapping_div(r),
+                    _ => l.wrapping_rem(r),
+                })
+            },
+        }
+    }
+
+    fn call_method(&mut self, method_decl: &AST::MethodDecl, args: Vec<Value>) -> Value {
+        let mut param_scope = HashMap::new();
+        for (arg_decl, arg_value) in method_decl.args.iter().zip(args) {
+            param_scope.insert(arg_decl.name.name.clone(), Variable::Scalar(arg_value));
+        }
+        self.frames.push(vec![param_scope]);
+        self.visit_block(&method_decl.body);
+        self.frames.pop();
+
+        if self.return_flag {
+            self.return_flag = false;
+            return std::mem::replace(&mut self.result_value, Value::Void);
+        }
+        if method_decl.type_name != "void" && !self.halted() {
+            self.runtime_error(&format!("Line {} - control reached the end of non-void method {} without a return.",
+                               method_decl.name.line_num, method_decl.name.name), NO_RETURN_EXIT);
+        }
+        Value::Void
+    }
+
+    fn call_import(&mut self, name: &str, args: Vec<Value>) -> Value {
+        let printed = match name {
+            "printf" => {
+                let fmt = match args.first() {
+                    Some(Value::Str(fmt)) => fmt.clone(),
+                    _ => String::new(),
+                };
+                format_printf(&fmt, &args[1.min(args.len())..])
+            },
+            "printint" | "printlong" | "print_int" | "print_long" => {
+                args.iter().map(|arg| arg.as_i64().to_string()).collect::<Vec<String>>().join(" ")
+            },
+            "printbool" | "print_bool" => {
+                args.iter().map(|arg| arg.as_bool().to_string()).collect::<Vec<String>>().join(" ")
+            },
+            "printchar" | "print_char" | "putchar" => {
+                args.iter().map(|arg| (arg.as_i64() as u8) as char).collect()
+            },
+            "printstr" | "print_string" | "puts" => {
+                let mut printed: String = args.iter().map(|arg| match arg {
+                    Value::Str(s) => s.clone(),
+                    _ => arg.as_i64().to_string(),
+                }).collect();
+                if name == "puts" {
+                    printed.push('\n');
+                }
+                printed
+            },
+            _ => {
+                self.runtime_error(&format!("import {} is not supported by the evaluator.", name), RUNTIME_ERROR_EXIT);
+                return Value::Int(0);
+            }
+        };
+        self.output.push_str(&printed);
+        Value::Int(printed.len() as i32)
+    }
+
+    fn run_loop_body(&mut self, block: &AST::Block) -> bool {
+        // returns whether the loop should stop
+        self.visit_block(block);
+        self.continue_flag = false;
+        if self.break_flag {
+            self.break_flag = false;
+            return true;
+        }
+        self.return_flag || self.halted()
+    }
+}
+
+impl Visitor for Evaluator {
+    fn visit_program(&mut self, program: &AST::Program) {
+        for field_decl in &program.fields {
+            self.visit_field_decl(field_decl);
+        }
+        for method_decl in &program.methods {
+            self.methods.insert(method_decl.name.name.clone(), Rc::new(method_decl.as_ref().clone()));
+        }
+        let main_decl = Rc::clone(&self.methods["main"]);
+        self.call_method(&main_decl, vec![]);
+    }
+
+    fn visit_field_decl(&mut self, field_decl: &AST::FieldDecl) {
+        for var_decl in &field_decl.vars {
+            self.visit_var_decl(var_decl);
+        }
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &AST::VarDecl) {
+        let var = if var_decl.is_array {
+            match var_decl.initializer.as_ref() {
+                Some(AST::ASTNode::ArrayLiteral(array_literal)) => {
+                    Variable::Array(array_literal.array_values.iter().map(|val| self.eval(val)).collect())
+                },
+                _ => {
+                    let array_len = match var_decl.array_len.as_ref() {
+                        Some(len) => parse_int(&len.value, len.is_neg) as usize,
+                        None => 0,
+                    };
+                    Variable::Array(vec![Value::zero(&var_decl.type_name); array_len])
+                },
+            }
+        } else {
+            match var_decl.initializer.as_ref() {
+                Some(initializer) => Variable::Scalar(self.eval(initializer)),
+                None => Variable::Scalar(Value::zero(&var_decl.type_name)),
+            }
+        };
+        self.declare_var(&var_decl.name.name, var);
+    }
+
+    fn visit_block(&mut self, block: &AST::Block) {
+        self.frames.last_mut().unwrap().push(HashMap::new());
+        for field_decl in &block.fields {
+            self.visit_field_decl(field_decl);
+        }
+        for statement in &block.statements {
+            if self.interrupted() {
+                break;
+            }
+            statement.accept(self);
+        }
+        self.frames.last_mut().unwrap().pop();
+    }
+
+    fn visit_if_statement(&mut self, if_statement: &AST::IfStatement) {
+        if self.eval(&if_statement.condition).as_bool() {
+            self.visit_block(&if_statement.then_block);
+        } else if let Some(else_block) = if_statement.else_block.as_ref() {
+            self.visit_block(else_block);
+        }
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &AST::ForStatement) {
+        self.visit_assignment(&for_statement.start_assignment);
+        while !self.halted() && self.eval(&for_statement.end_expr).as_bool() {
+            if self.run_loop_body(&for_statement.block) {
+                break;
+            }
+            for_statement.update_expr.accept(self);
+        }
+    }
+
+    fn visit_while_statement(&mut self, while_statement: &AST::WhileStatement) {
+        while !self.halted() && self.eval(&while_statement.condition).as_bool() {
+            if self.run_loop_body(&while_statement.block) {
+                break;
+            }
+        }
+    }
+
+    fn visit_return_statement(&mut self, return_statement: &AST::ReturnStatement) {
+        let return_value = match return_statement.expr.as_ref() {
+            Some(expr) => self.eval(expr),
+            None => Value::Void,
+        };
+        self.result_value = return_value;
+        self.return_flag = true;
+    }
+
+    fn visit_statement_control(&mut self, statement_control: &AST::StatementControl) {
+        match statement_control.op.as_str() {
+            "break" => self.break_flag = true,
+            _ => self.continue_flag = true,
+        }
+    }
+
+    fn visit_assignment(&mut self, assignment: &AST::Assignment) {
+        let location = assignment.assign_var.as_ref();
+        // plain assignments evaluate the value before the location, compound ones the location first
+        let (slot, new_value) = match (assignment.assign_op.as_str(), assignment.expr.as_ref()) {
+            ("=", Some(expr)) => {
+                let value = self.eval(expr);
+                if self.halted() {
+                    return;
+                }
+                (self.resolve_location(location), value)
+            },
+            ("++", None) | ("--", None) => {
+                let slot = match self.resolve_location(location) {
+                    Some(slot) => slot,
+                    None => return,
+                };
+                let cur_value = self.read_location(&slot);
+                let one = match cur_value {
+                    Value::Long(_) => Value::Long(1),
+                    _ => Value::Int(1),
+                };
+                let new_value = self.arith(&assignment.assign_op[..1], cur_value, one);
+                (Some(slot), new_value)
+            },
+            (op, Some(expr)) => {
+                let slot = match self.resolve_location(location) {
+                    Some(slot) => slot,
+                    None => return,
+                };
+                let cur_value = self.read_location(&slot);
+                let rhs_value = self.eval(expr);
+                let new_value = self.arith(&op[..1], cur_value, rhs_value);
+                (Some(slot), new_value)
+            },
+            _ => return,
+        };
+        if let Some(slot) = slot {
+            if !self.halted() {
+                self.write_location(&slot, new_value);
+            }
+        }
+    }
+
+    fn visit_method_call(&mut self, method_call: &AST::MethodCall) {
+        let mut args = vec![];
+        for arg in &method_call.args {
+            args.push(self.eval(arg));
+        }
+        if self.halted() {
+            return;
+        }
+        let method_name = method_call.name.name.as_str();
+        self.result_value = match self.methods.get(method_name).cloned() {
+            Some(method_decl) => self.call_method(&method_decl, args),
+            None => self.call_import(method_name, args),
+        };
+    }
+
+    fn visit_len_call(&mut self, len_call: &AST::LenCall) {
+        self.result_value = match self.lookup_var(&len_call.id.name) {
+            Variable::Array(values) => Value::Int(values.len() as i32),
+            Variable::Scalar(_) => Value::Int(0),
+        };
+    }
+
+    fn visit_int_cast(&mut self, int_cast: &AST::IntCast) {
+        self.result_value = Value::Int(self.eval(&int_cast.cast_expr).as_i64() as i32);
+    }
+
+    fn visit_long_cast(&mut self, long_cast: &AST::LongCast) {
+        self.result_value = Value::Long(self.eval(&long_cast.cast_expr).as_i64());
+    }
+
+    fn visit_unary_expression(&mut self, unary_expression: &AST::UnaryExpression) {
+        let value = self.eval(&unary_expression.expr);
+        self.result_value = match (unary_expression.op.as_str(), value) {
+            ("!", value) => Value::Bool(!value.as_bool()),
+            (_, Value::Int(v)) => Value::Int(v.wrapping_neg()),
+            (_, value) => Value::Long(value.as_i64().wrapping_neg()),
+        };
+    }
+
+    fn visit_binary_expression(&mut self, binary_expression: &AST::BinaryExpression) {
+        let op = binary_expression.op.as_str();
+        let left = self.eval(&binary_expression.left_expr);
+        // && and || short circuit
+        match op {
+            "&&" if !left.as_bool() => {
+                self.result_value = Value::Bool(false);
+                return;
+            },
+            "||" if left.as_bool() => {
+                self.result_value = Value::Bool(true);
+                return;
+            },
+            _ => (),
+        }
+        let right = self.eval(&binary_expression.right_expr);
+        self.result_value = match op {
+            "+" | "-" | "*" | "/" | "%" => self.arith(op, left, right),
+            "<" => Value::Bool(left.as_i64() < right.as_i64()),
+            "<=" => Value::Bool(left.as_i64() <= right.as_i64()),
+            ">" => Value::Bool(left.as_i64() > right.as_i64()),
+            ">=" => Value::Bool(left.as_i64() >= right.as_i64()),
+            "==" => Value::Bool(left == right),
+            "!=" => Value::Bool(left != right),
+            _ => Value::Bool(right.as_bool()), // && and || that were not short circuited
+        };
+    }
+
+    fn visit_index_expression(&mut self, index_expression: &AST::IndexExpression) {
+        self.result_value = match self.eval_array_index(index_expression) {
+            Some(ind) => match self.lookup_var(&index_expression.id.name) {
+                Variable::Array(values) => values[ind].clone(),
+                Variable::Scalar(_) => Value::Void,
+            },
+            None => Value::Int(0),
+        };
+    }
+
+    fn visit_identifier(&mut self, identifier: &AST::Identifier) {
+        self.result_value = match self.lookup_var(&identifier.name) {
+            Variable::Scalar(value) => value.clone(),
+            Variable::Array(_) => Value::Void,
+        };
+    }
+
+    fn visit_int_constant(&mut self, int_constant: &AST::IntConstant) {
+        self.result_value = Value::Int(parse_int(&int_constant.value, int_constant.is_neg) as i32);
+    }
+
+    fn visit_long_constant(&mut self, long_constant: &AST::LongConstant) {
+        self.result_value = Value::Long(parse_int(&long_constant.value, long_constant.is_neg));
+    }
+
+    fn visit_string_constant(&mut self, string_constant: &AST::StringConstant) {
+        let value = &string_constant.value;
+        self.result_value = Value::Str(unescape(&value[1..value.len() - 1]));
+    }
+
+    fn visit_bool_constant(&mut self, bool_constant: &AST::BoolConstant) {
+        self.result_value = Value::Bool(bool_constant.value);
+    }
+
+    fn visit_char_constant(&mut self, char_constant: &AST::CharConstant) {
+        let char_value = unescape(&char_constant.value).chars().next().unwrap_or('\0');
+        self.result_value = Value::Int(char_value as i32);
+    }
+}
+
+// parse decimal or hex literal text, wrapping on overflow like the generated code would
+fn parse_int(value: &str, is_neg: bool) -> i64 {
+    let magnitude = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).unwrap_or(0),
+        None => value.parse::<u64>().unwrap_or(0),
+    } as i64;
+    if is_neg {
+        magnitude.wrapping_neg()
+    } else {
+        magnitude
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/*
+Minimal printf supporting %d %i %u %x %c %s %% with optional -/0 flags, width and l/ll length
+*/
+fn format_printf(fmt: &str, args: &[Value]) -> String {
+    let mut printed = String::new();
+    let mut args = args.iter();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            printed.push(c);
+            continue;
+        }
+        let mut flags = String::new();
+        while let Some(&flag) = chars.peek() {
+            if flag != '-' && flag != '0' {
+                break;
+            }
+            flags.push(flag);
+            chars.next();
+        }
+        let mut width = String::new();
+        while let Some(&digit) = chars.peek() {
+            if !digit.is_ascii_digit() {
+                break;
+            }
+            width.push(digit);
+            chars.next();
+        }
+        while chars.peek() == Some(&'l') {
+            chars.next();
+        }
+        let arg = args.next().cloned().unwrap_or(Value::Int(0));
+        let formatted = match chars.next() {
+            Some('d') | Some('i') => arg.as_i64().to_string(),
+            Some('u') => match arg {
+                Value::Int(v) => (v as u32).to_string(),
+                _ => (arg.as_i64() as u64).to_string(),
+            },
+            Some('x') => match arg {
+                Value::Int(v) => format!("{:x}", v),
+                _ => format!("{:x}", arg.as_i64()),
+            },
+            Some('c') => ((arg.as_i64() as u8) as char).to_string(),
+            Some('s') => match arg {
+                Value::Str(s) => s,
+                _ => arg.as_i64().to_string(),
+            },
+            Some('%') => "%".to_string(),
+            Some(other) => format!("%{}", other),
+            None => "%".to_string(),
+        };
+        let width = width.parse::<usize>().unwrap_or(0);
+        if flags.contains('-') {
+            printed.push_str(&format!("{:<width$}", formatted, width = width));
+        } else if flags.contains('0') {
+            // the zeros go between the sign and the digits
+            let (sign, digits) = match formatted.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", formatted.as_str()),
+            };
+            printed.push_str(&format!("{}{:0>width$}", sign, digits, width = width.saturating_sub(sign.len())));
+        } else {
+            printed.push_str(&format!("{:>width$}", formatted, width = width));
+        }
+    }
+    printed
+}
+
+/*
+Run a semantically checked program, returning its printed output and exit code
+*/
+pub fn evaluate_program(ast: AST::Program, debug: bool) -> (String, i32) {
+    let eval_thread = std::thread::Builder::new()
+        .stack_size(EVAL_STACK_SIZE)
+        .spawn(move || {
+            let mut evaluator = Evaluator {
+                globals: HashMap::new(),
+                frames: vec![],
+                methods: HashMap::new(),
+                output: String::new(),
+                break_flag: false,
+                continue_flag: false,
+                return_flag: false,
+                exit_code: None,
+                result_value: Value::Void,
+                debug: debug,
+            };
+            ast.accept(&mut evaluator);
+            if evaluator.debug {
+                eprintln!("Evaluator finished with globals: {:?}", evaluator.globals);
+            }
+            (evaluator.output, evaluator.exit_code.unwrap_or(0))
+        })
+        .expect("Failed to spawn evaluator thread");
+    eval_thread.join().expect("Evaluator thread panicked")
+}
+
+pub fn run(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, lints: &LintConfig, debug: bool) {
+    let analyzed_program = match check_file(input, lints, debug) {
+        Ok((analyzed_program, warnings)) => {
+            for warning in warnings {
+                eprintln!("{}", warning);
+            }
+            analyzed_program
+        },
+        Err(errors) => {
+            for error in errors {
+                writeln!(writer, "{}", error).unwrap();
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let (output, exit_code) = evaluate_program(analyzed_program.ast, debug);
+    write!(writer, "{}", output).unwrap();
+    writer.flush().unwrap();
+    std::process::exit(exit_code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_printf, Value};
+
+    fn run(source: &str) -> (String, i32) {
+        crate::evaluate(source).expect("test program should pass semantic checks")
+    }
+
+    #[test]
+    fn compound_assignment_evaluates_index_once() {
+        let (output, exit_code) = run(r#"
+            import printf;
+            int g;
+            int a[4];
+            int f() { g += 1; return 1; }
+            void main() {
+                a[f()] += 1;
+                a[f()] ++;
+                printf("%d %d", g, a[1]);
+            }
+        "#);
+        assert_eq!(exit_code, 0);
+        assert_eq!(output, "2 2");
+    }
+
+    #[test]
+    fn compound_assignment_out_of_bounds_halts() {
+        let (_, exit_code) = run(r#"
+            int a[2];
+            void main() { a[5] += 1; }
+        "#);
+        assert_eq!(exit_code, -1);
+    }
+
+    #[test]
+    fn printf_integer_conversions() {
+        assert_eq!(format_printf("%d %i", &[Value::Int(-7), Value::Long(8)]), "-7 8");
+        assert_eq!(format_printf("%u %lu", &[Value::Int(-1), Value::Long(-1)]), "4294967295 18446744073709551615");
+        assert_eq!(format_printf("%x %lx", &[Value::Int(-1), Value::Long(255)]), "ffffffff ff");
+    }
+
+    #[test]
+    fn printf_width_and_flags() {
+        assert_eq!(format_printf("[%5d]", &[Value::Int(42)]), "[   42]");
+        assert_eq!(format_printf("[%-5d]", &[Value::Int(42)]), "[42   ]");
+        assert_eq!(format_printf("[%05d]", &[Value::Int(42)]), "[00042]");
+        assert_eq!(format_printf("[%05d]", &[Value::Int(-42)]), "[-0042]");
+        assert_eq!(format_printf("[%05ld]", &[Value::Long(-123456)]), "[-123456]");
+        assert_eq!(format_printf("[%5d|%-5d]", &[Value::Int(-42), Value::Int(-42)]), "[  -42|-42  ]");
+    }
+
+    #[test]
+    fn printf_chars_strings_and_percent() {
+        let args = [Value::Int('A' as i32), Value::Str("hi".to_string())];
+        assert_eq!(format_printf("%c %s 100%%", &args), "A hi 100%");
+        // missing arguments print as zero
+        assert_eq!(format_printf("%d", &[]), "0");
+    }
+}
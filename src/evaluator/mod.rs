@@ -0,0 +1 @@
+pub mod evaluator;
\ No newline at end of file
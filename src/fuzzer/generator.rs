@@ -0,0 +1,660 @@
+use super::super::parser::AST;
+use super::super::parser::parser_printer::ParserPrinter;
+
+/*
+Generates random decaf programs that pass semantic checking, always terminate and never index
+arrays out of bounds:
+- methods only call methods declared before them, so there is no recursion
+- loops count a dedicated loop variable up to a small bound, and the body never writes it
+- array indices are wrapped into range with ((e % len) + len) % len
+- divisors are positive literals
+*/
+
+// limits on the size of generated programs
+const MAX_GLOBALS: usize = 5;
+const MAX_METHODS: usize = 4;
+const MAX_PARAMS: usize = 3;
+const MAX_LOCALS: usize = 4;
+const MAX_STATEMENTS: usize = 6;
+const MAX_EXPR_DEPTH: u32 = 3;
+const MAX_BLOCK_DEPTH: u32 = 3;
+const MAX_LOOP_DEPTH: u32 = 2;
+const MAX_LOOP_BOUND: i64 = 5;
+const MAX_ARRAY_LEN: i64 = 8;
+
+// xorshift64* so generated programs are reproducible from a seed without extra dependencies
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    // uniform value in [0, n)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low + 1) as u64) as i64
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+enum GenType {
+    Int,
+    Long,
+    Bool,
+}
+
+impl GenType {
+    fn type_name(&self) -> &'static str {
+        match self {
+            GenType::Int => "int",
+            GenType::Long => "long",
+            GenType::Bool => "bool",
+        }
+    }
+}
+
+#[derive(Clone)]
+struct GenVar {
+    name: String,
+    var_type: GenType,
+    array_len: Option<i64>,
+    is_const: bool,
+    is_loop_var: bool, // loop counters are never written outside their loop header
+}
+
+#[derive(Clone)]
+struct GenMethod {
+    name: String,
+    return_type: Option<GenType>, // None for void
+    param_types: Vec<GenType>,
+}
+
+struct Generator {
+    rng: Rng,
+    scopes: Vec<Vec<GenVar>>, // visible variables, innermost scope last
+    methods: Vec<GenMethod>, // methods declared so far and callable from the current method
+    name_count: usize,
+
+    // state of the method being generated
+    return_type: Option<GenType>,
+    loop_depth: u32,
+    block_depth: u32,
+}
+
+impl Generator {
+    fn fresh_name(&mut self, prefix: &str) -> String {
+        self.name_count += 1;
+        format!("{}{}", prefix, self.name_count)
+    }
+
+    fn random_type(&mut self) -> GenType {
+        match self.rng.below(3) {
+            0 => GenType::Int,
+            1 => GenType::Long,
+            _ => GenType::Bool,
+        }
+    }
+
+    fn visible_vars(&self) -> impl Iterator<Item = &GenVar> {
+        self.scopes.iter().flatten()
+    }
+
+    fn pick_var(&mut self, var_type: GenType, is_array: bool, writable: bool) -> Option<GenVar> {
+        let candidates: Vec<GenVar> = self.visible_vars()
+            .filter(|var| var.var_type == var_type && var.array_len.is_some() == is_array)
+            .filter(|var| !writable || (!var.is_const && !var.is_loop_var))
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let ind = self.rng.below(candidates.len());
+        Some(candidates[ind].clone())
+    }
+
+    fn identifier(&self, name: &str, status: i32) -> AST::Identifier {
+        AST::Identifier {
+            name: name.to_string(),
+            status: status,
+            line_num: 0,
+            node_id: AST::new_node_id(),
+        }
+    }
+
+    fn int_literal(&self, value: i64) -> AST::ASTNode {
+        AST::ASTNode::IntConstant(AST::IntConstant {
+            is_neg: value < 0,
+            value: value.unsigned_abs().to_string(),
+            node_id: AST::new_node_id(),
+        })
+    }
+
+    fn literal(&mut self, var_type: GenType) -> AST::ASTNode {
+        match var_type {
+            GenType::Int => {
+                let value = if self.rng.chance(80) { self.rng.range(-20, 100) } else { self.rng.range(i32::MIN as i64, i32::MAX as i64) };
+                self.int_literal(value)
+            },
+            GenType::Long => {
+                let value = if self.rng.chance(80) { self.rng.range(-20, 100) } else { self.rng.next() as i64 };
+                AST::ASTNode::LongConstant(AST::LongConstant {
+                    is_neg: value < 0,
+                    value: value.unsigned_abs().to_string(),
+                    node_id: AST::new_node_id(),
+                })
+            },
+            GenType::Bool => AST::ASTNode::BoolConstant(AST::BoolConstant {
+                value: self.rng.chance(50),
+                node_id: AST::new_node_id(),
+            }),
+        }
+    }
+
+    fn binary(&self, op: &str, left: AST::ASTNode, right: AST::ASTNode) -> AST::ASTNode {
+        AST::ASTNode::BinaryExpression(AST::BinaryExpression {
+            op: op.to_string(),
+            left_expr: Box::new(left),
+            right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
+        })
+    }
+
+    // ((idx % len) + len) % len is always a valid index
+    fn wrap_index(&mut self, array_len: i64) -> AST::ASTNode {
+        if self.rng.chance(40) {
+            let ind = self.rng.range(0, array_len - 1);
+            return self.int_literal(ind);
+        }
+        let idx_expr = self.expression(GenType::Int, 1);
+        let rem = self.binary("%", idx_expr, self.int_literal(array_len));
+        let shifted = self.binary("+", rem, self.int_literal(array_len));
+        self.binary("%", shifted, self.int_literal(array_len))
+    }
+
+    fn location(&mut self, var: &GenVar, status: i32) -> AST::ASTNode {
+        match var.array_len {
+            Some(array_len) => AST::ASTNode::IndexExpression(AST::IndexExpression {
+                id: Box::new(self.identifier(&var.name, status)),
+                idx_expr: Box::new(self.wrap_index(array_len)),
+                node_id: AST::new_node_id(),
+            }),
+            None => AST::ASTNode::Identifier(self.identifier(&var.name, status)),
+        }
+    }
+
+    fn method_call(&mut self, method: &GenMethod, depth: u32) -> AST::MethodCall {
+        let args = method.param_types.iter()
+            .map(|param_type| Box::new(self.expression(*param_type, depth + 1)))
+            .collect();
+        AST::MethodCall {
+            name: Box::new(self.identifier(&method.name, 1)),
+            args: args,
+            node_id: AST::new_node_id(),
+        }
+    }
+
+    fn expression(&mut self, var_type: GenType, depth: u32) -> AST::ASTNode {
+        // leaves: literals, variables and array elements
+        if depth >= MAX_EXPR_DEPTH || self.rng.chance(30) {
+            let is_array = self.rng.chance(30);
+            return match self.pick_var(var_type, is_array, false) {
+                Some(var) => self.location(&var, 1),
+                None => self.literal(var_type),
+            };
+        }
+
+        // calls to methods declared earlier
+        if self.rng.chance(15) {
+            let callable: Vec<GenMethod> = self.methods.iter()
+                .filter(|method| method.return_type == Some(var_type))
+                .cloned()
+                .collect();
+            if !callable.is_empty() {
+                let method = callable[self.rng.below(callable.len())].clone();
+                return AST::ASTNode::MethodCall(self.method_call(&method, depth));
+            }
+        }
+
+        match var_type {
+            GenType::Int | GenType::Long => {
+                match self.rng.below(6) {
+                    0 => AST::ASTNode::UnaryExpression(AST::UnaryExpression {
+                        op: "-".to_string(),
+                        expr: Box::new(self.expression(var_type, depth + 1)),
+                        node_id: AST::new_node_id(),
+                    }),
+                    1 if var_type == GenType::Int => {
+                        match self.pick_var(GenType::Int, true, false)
+                            .or_else(|| self.pick_var(GenType::Long, true, false))
+                            .or_else(|| self.pick_var(GenType::Bool, true, false)) {
+                            Some(array) => AST::ASTNode::LenCall(AST::LenCall {
+                                id: Box::new(self.identifier(&array.name, 1)),
+                                node_id: AST::new_node_id(),
+                            }),
+                            None => self.literal(var_type),
+                        }
+                    },
+                    1 => AST::ASTNode::LongCast(AST::LongCast {
+                        cast_expr: Box::new(self.expression(GenType::Int, depth + 1)),
+                        node_id: AST::new_node_id(),
+                    }),
+                    2 if var_type == GenType::Int => AST::ASTNode::IntCast(AST::IntCast {
+                        cast_expr: Box::new(self.expression(GenType::Long, depth + 1)),
+                        node_id: AST::new_node_id(),
+                    }),
+                    3 => {
+                        // divisors are positive literals so there is no division by zero or MIN / -1
+                        let op = if self.rng.chance(50) { "/" } else { "%" };
+                        let left = self.expression(var_type, depth + 1);
+                        let divisor = self.rng.range(1, 9);
+                        let right = match var_type {
+                            GenType::Long => AST::ASTNode::LongConstant(AST::LongConstant {
+                                is_neg: false,
+                                value: divisor.to_string(),
+                                node_id: AST::new_node_id(),
+                            }),
+                            _ => self.int_literal(divisor),
+                        };
+                        self.binary(op, left, right)
+                    },
+                    _ => {
+                        let op = ["+", "-", "*"][self.rng.below(3)];
+                        let left = self.expression(var_type, depth + 1);
+                        let right = self.expression(var_type, depth + 1);
+                        self.binary(op, left, right)
+                    },
+                }
+            },
+            GenType::Bool => {
+                match self.rng.below(5) {
+                    0 => AST::ASTNode::UnaryExpression(AST::UnaryExpression {
+                        op: "!".to_string(),
+                        expr: Box::new(self.expression(GenType::Bool, depth + 1)),
+                        node_id: AST::new_node_id(),
+                    }),
+                    1 => {
+                        let op = if self.rng.chance(50) { "&&" } else { "||" };
+                        let left = self.expression(GenType::Bool, depth + 1);
+                        let right = self.expression(GenType::Bool, depth + 1);
+                        self.binary(op, left, right)
+                    },
+                    2 => {
+                        let operand_type = self.random_type();
+                        let op = if self.rng.chance(50) { "==" } else { "!=" };
+                        let left = self.expression(operand_type, depth + 1);
+                        let right = self.expression(operand_type, depth + 1);
+                        self.binary(op, left, right)
+                    },
+                    _ => {
+                        let operand_type = if self.rng.chance(50) { GenType::Int } else { GenType::Long };
+                        let op = ["<", "<=", ">", ">="][self.rng.below(4)];
+                        let left = self.expression(operand_type, depth + 1);
+                        let right = self.expression(operand_type, depth + 1);
+                        self.binary(op, left, right)
+                    },
+                }
+            },
+        }
+    }
+
+    fn var_decls(&mut self, prefix: &str, count: usize, allow_const: bool) -> Vec<Box<AST::FieldDecl>> {
+        let mut field_decls = vec![];
+        for _ in 0..count {
+            let var_type = self.random_type();
+            let is_const = allow_const && self.rng.chance(20);
+            let name = self.fresh_name(prefix);
+            let mut var_decl = AST::VarDecl {
+                name: Box::new(self.identifier(&name, 0)),
+                is_const: is_const,
+                type_name: var_type.type_name().to_string(),
+                is_array: false,
+                array_len: Box::new(None),
+                initializer: Box::new(None),
+            };
+            let mut array_len = None;
+            if !is_const && self.rng.chance(30) {
+                let len = self.rng.range(1, MAX_ARRAY_LEN);
+                var_decl.is_array = true;
+                array_len = Some(len);
+                if self.rng.chance(50) {
+                    let values = (0..len).map(|_| Box::new(self.literal(var_type))).collect();
+                    var_decl.initializer = Box::new(Some(AST::ASTNode::ArrayLiteral(AST::ArrayLiteral {
+                        array_values: values,
+                    })));
+                } else {
+                    var_decl.array_len = Box::new(Some(AST::IntConstant {
+                        is_neg: false,
+                        value: len.to_string(),
+                        node_id: AST::new_node_id(),
+                    }));
+                }
+            } else if is_const || self.rng.chance(40) {
+                var_decl.initializer = Box::new(Some(self.literal(var_type)));
+            }
+            self.scopes.last_mut().unwrap().push(GenVar {
+                name: name,
+                var_type: var_type,
+                array_len: array_len,
+                is_const: is_const,
+                is_loop_var: false,
+            });
+            field_decls.push(Box::new(AST::FieldDecl {
+                type_name: var_type.type_name().to_string(),
+                is_const: is_const,
+                vars: vec![Box::new(var_decl)],
+            }));
+        }
+        field_decls
+    }
+
+    fn print_statement(&mut self, var_type: GenType, expr: AST::ASTNode) -> AST::ASTNode {
+        let format = match var_type {
+            GenType::Long => "\"%ld\\n\"",
+            _ => "\"%d\\n\"",
+        };
+        AST::ASTNode::MethodCall(AST::MethodCall {
+            name: Box::new(self.identifier("printf", 1)),
+            args: vec![
+                Box::new(AST::ASTNode::StringConstant(AST::StringConstant { value: format.to_string(), node_id: AST::new_node_id() })),
+                Box::new(expr),
+            ],
+            node_id: AST::new_node_id(),
+        })
+    }
+
+    fn assignment(&mut self) -> Option<AST::ASTNode> {
+        let var_type = self.random_type();
+        let is_array = self.rng.chance(30);
+        let var = self.pick_var(var_type, is_array, true)?;
+        let location = self.location(&var, 2);
+        let assign_op = match var_type {
+            GenType::Bool => "=",
+            _ => ["=", "=", "+=", "-=", "*=", "++", "--"][self.rng.below(7)],
+        };
+        let expr = match assign_op {
+            "++" | "--" => None,
+            _ => Some(self.expression(var_type, 0)),
+        };
+        Some(AST::ASTNode::Assignment(AST::Assignment {
+            assign_var: Box::new(location),
+            assign_op: assign_op.to_string(),
+            expr: Box::new(expr),
+        }))
+    }
+
+    fn block(&mut self, locals: usize) -> AST::Block {
+        self.block_depth += 1;
+        self.scopes.push(vec![]);
+        let fields = self.var_decls("v", locals, true);
+        let mut statements = vec![];
+        for _ in 0..self.rng.range(1, MAX_STATEMENTS as i64) {
+            if let Some(statement) = self.statement() {
+                statements.push(Box::new(statement));
+            }
+        }
+        self.scopes.pop();
+        self.block_depth -= 1;
+        AST::Block {
+            fields: fields,
+            statements: statements,
+        }
+    }
+
+    fn loop_bound(&mut self, counter: &str) -> AST::ASTNode {
+        let bound = self.rng.range(0, MAX_LOOP_BOUND);
+        let bound = self.int_literal(bound);
+        self.binary("<", AST::ASTNode::Identifier(self.identifier(counter, 1)), bound)
+    }
+
+    fn increment(&self, counter: &str) -> AST::Assignment {
+        AST::Assignment {
+            assign_var: Box::new(AST::ASTNode::Identifier(self.identifier(counter, 2))),
+            assign_op: "+=".to_string(),
+            expr: Box::new(Some(self.int_literal(1))),
+        }
+    }
+
+    // loop counters live in their own scope around the loop so nothing else can write them
+    fn loop_statement(&mut self) -> AST::ASTNode {
+        let counter = self.fresh_name("i");
+        self.scopes.push(vec![GenVar {
+            name: counter.clone(),
+            var_type: GenType::Int,
+            array_len: None,
+            is_const: false,
+            is_loop_var: true,
+        }]);
+        self.loop_depth += 1;
+        let loop_statement = if self.rng.chance(50) {
+            let locals = self.rng.below(2);
+            let block = self.block(locals);
+            AST::ASTNode::ForStatement(AST::ForStatement {
+                start_assignment: Box::new(AST::Assignment {
+                    assign_var: Box::new(AST::ASTNode::Identifier(self.identifier(&counter, 2))),
+                    assign_op: "=".to_string(),
+                    expr: Box::new(Some(self.int_literal(0))),
+                }),
+                end_expr: Box::new(self.loop_bound(&counter)),
+                update_expr: Box::new(AST::ASTNode::Assignment(self.increment(&counter))),
+                block: Box::new(block),
+                line_num: 0,
+            })
+        } else {
+            // the counter is incremented first so continue can not skip it
+            let locals = self.rng.below(2);
+            let mut block = self.block(locals);
+            block.statements.insert(0, Box::new(AST::ASTNode::Assignment(self.increment(&counter))));
+            AST::ASTNode::WhileStatement(AST::WhileStatement {
+                condition: Box::new(self.loop_bound(&counter)),
+                block: Box::new(block),
+                line_num: 0,
+            })
+        };
+        self.loop_depth -= 1;
+        self.scopes.pop();
+
+        // wrap in a block declaring the counter, initialized for while loops
+        let counter_decl = AST::FieldDecl {
+            type_name: "int".to_string(),
+            is_const: false,
+            vars: vec![Box::new(AST::VarDecl {
+                name: Box::new(self.identifier(&counter, 0)),
+                is_const: false,
+                type_name: "int".to_string(),
+                is_array: false,
+                array_len: Box::new(None),
+                initializer: Box::new(None),
+            })],
+        };
+        AST::ASTNode::IfStatement(AST::IfStatement {
+            condition: Box::new(AST::ASTNode::BoolConstant(AST::BoolConstant { value: true, node_id: AST::new_node_id() })),
+            then_block: Box::new(AST::Block {
+                fields: vec![Box::new(counter_decl)],
+                statements: vec![Box::new(loop_statement)],
+            }),
+            else_block: Box::new(None),
+            line_num: 0,
+        })
+    }
+
+    fn statement(&mut self) -> Option<AST::ASTNode> {
+        let nested_ok = self.block_depth < MAX_BLOCK_DEPTH;
+        match self.rng.below(10) {
+            0..=2 => self.assignment(),
+            3 => {
+                let var_type = self.random_type();
+                let expr = self.expression(var_type, 0);
+                Some(self.print_statement(var_type, expr))
+            },
+            4 if nested_ok => {
+                let condition = self.expression(GenType::Bool, 0);
+                let locals = self.rng.below(2);
+                let then_block = self.block(locals);
+                let locals = self.rng.below(2);
+                let else_block = if self.rng.chance(50) { Some(self.block(locals)) } else { None };
+                Some(AST::ASTNode::IfStatement(AST::IfStatement {
+                    condition: Box::new(condition),
+                    then_block: Box::new(then_block),
+                    else_block: Box::new(else_block),
+                    line_num: 0,
+                }))
+            },
+            5 if nested_ok && self.loop_depth < MAX_LOOP_DEPTH => Some(self.loop_statement()),
+            6 if !self.methods.is_empty() => {
+                let method = self.methods[self.rng.below(self.methods.len())].clone();
+                Some(AST::ASTNode::MethodCall(self.method_call(&method, 0)))
+            },
+            7 if self.loop_depth > 0 && nested_ok => {
+                // guard break/continue so the rest of the loop body stays reachable
+                let op = if self.rng.chance(50) { "break" } else { "continue" };
+                let condition = self.expression(GenType::Bool, 1);
+                Some(AST::ASTNode::IfStatement(AST::IfStatement {
+                    condition: Box::new(condition),
+                    then_block: Box::new(AST::Block {
+                        fields: vec![],
+                        statements: vec![Box::new(AST::ASTNode::StatementControl(AST::StatementControl {
+                            op: op.to_string(),
+                            line_num: 0,
+                        }))],
+                    }),
+                    else_block: Box::new(None),
+                    line_num: 0,
+                }))
+            },
+            8 if self.block_depth > 1 && self.rng.chance(30) => Some(self.return_statement()),
+            _ => self.assignment(),
+        }
+    }
+
+    fn return_statement(&mut self) -> AST::ASTNode {
+        let expr = self.return_type.map(|return_type| self.expression(return_type, 0));
+        AST::ASTNode::ReturnStatement(AST::ReturnStatement {
+            func_type: self.return_type.map_or("void", |return_type| return_type.type_name()).to_string(),
+            expr: Box::new(expr),
+            line_num: 0,
+        })
+    }
+
+    fn method_decl(&mut self, name: &str, return_type: Option<GenType>, param_count: usize) -> AST::MethodDecl {
+        self.return_type = return_type;
+        let mut params = vec![];
+        let mut args = vec![];
+        for _ in 0..param_count {
+            let param_type = self.random_type();
+            let param_name = self.fresh_name("p");
+            args.push(Box::new(AST::MethodArgDecl {
+                type_name: param_type.type_name().to_string(),
+                name: Box::new(self.identifier(&param_name, 0)),
+            }));
+            params.push(GenVar {
+                name: param_name,
+                var_type: param_type,
+                array_len: None,
+                is_const: false,
+                is_loop_var: false,
+            });
+        }
+        self.scopes.push(params);
+        let locals = self.rng.below(MAX_LOCALS + 1);
+        let mut body = self.block(locals);
+        // non-void methods always end with a return
+        if return_type.is_some() {
+            self.scopes.push(vec![]);
+            body.statements.push(Box::new(self.return_statement()));
+            self.scopes.pop();
+        }
+        self.scopes.pop();
+
+        AST::MethodDecl {
+            type_name: return_type.map_or("void", |return_type| return_type.type_name()).to_string(),
+            name: self.identifier(name, 0),
+            args: args,
+            body: Box::new(body),
+        }
+    }
+}
+
+pub fn generate_program(seed: u64) -> AST::Program {
+    let mut generator = Generator {
+        // xorshift state must be non-zero
+        rng: Rng { state: seed.wrapping_mul(0x9E3779B97F4A7C15) | 1 },
+        scopes: vec![vec![]],
+        methods: vec![],
+        name_count: 0,
+        return_type: None,
+        loop_depth: 0,
+        block_depth: 0,
+    };
+
+    let imports = vec![Box::new(AST::ImportDecl {
+        import_id: generator.identifier("printf", 0),
+    })];
+    let global_count = generator.rng.below(MAX_GLOBALS + 1);
+    let fields = generator.var_decls("g", global_count, true);
+
+    let mut methods = vec![];
+    for _ in 0..generator.rng.below(MAX_METHODS + 1) {
+        let name = generator.fresh_name("m");
+        let return_type = if generator.rng.chance(25) { None } else { Some(generator.random_type()) };
+        let param_count = generator.rng.below(MAX_PARAMS + 1);
+        let method_decl = generator.method_decl(&name, return_type, param_count);
+        generator.methods.push(GenMethod {
+            name: name,
+            return_type: return_type,
+            param_types: method_decl.args.iter().map(|arg| match arg.type_name.as_str() {
+                "int" => GenType::Int,
+                "long" => GenType::Long,
+                _ => GenType::Bool,
+            }).collect(),
+        });
+        methods.push(Box::new(method_decl));
+    }
+
+    // main prints every global scalar at the end so the program state is observable
+    let mut main_decl = generator.method_decl("main", None, 0);
+    for var in generator.scopes[0].clone() {
+        if var.array_len.is_none() {
+            let read = AST::ASTNode::Identifier(generator.identifier(&var.name, 1));
+            main_decl.body.statements.push(Box::new(generator.print_statement(var.var_type, read)));
+        }
+    }
+    methods.push(Box::new(main_decl));
+
+    AST::Program {
+        imports: imports,
+        fields: fields,
+        methods: methods,
+    }
+}
+
+pub fn generate(seed: Option<u64>, mut writer: Box<dyn std::io::Write>, debug: bool) {
+    let seed = seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+    if debug {
+        eprintln!("Generating program with seed {}", seed);
+    }
+    let program = generate_program(seed);
+    let mut printer = ParserPrinter::new();
+    program.accept(&mut printer);
+    writeln!(writer, "// generated with seed {}", seed).unwrap();
+    write!(writer, "{}", printer.output).unwrap();
+}
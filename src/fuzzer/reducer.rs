@@ -0,0 +1,384 @@
+use super::super::parser::AST;
+use super::super::parser::parser::parse_file;
+use super::super::parser::parser_printer::ParserPrinter;
+
+use std::path::{Path, PathBuf};
+
+/*
+Test case reducer. Candidate programs are produced by applying a single reduction to the current
+best program and kept when the interestingness command still succeeds (exits 0) on them.
+Reductions are tried coarsest first and the whole sequence is repeated until nothing applies.
+*/
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+enum ReductionKind {
+    Declarations, // remove methods, imports and global or local variables
+    Statements, // remove statements, drop else branches, replace if/loops with their bodies
+    Expressions, // replace expressions with an operand or a constant
+}
+
+const REDUCTION_KINDS: [ReductionKind; 3] = [
+    ReductionKind::Declarations,
+    ReductionKind::Statements,
+    ReductionKind::Expressions,
+];
+
+/*
+Walks a program mutably, counting reduction sites of one kind and applying the site numbered target
+*/
+struct ReductionSite {
+    kind: ReductionKind,
+    target: usize,
+    count: usize,
+}
+
+impl ReductionSite {
+    // whether the next site is the one to reduce
+    fn hit(&mut self) -> bool {
+        self.count += 1;
+        self.count - 1 == self.target
+    }
+
+    fn applied(&self) -> bool {
+        self.count > self.target
+    }
+
+    fn reduce_program(&mut self, program: &mut AST::Program) {
+        if self.kind == ReductionKind::Declarations {
+            if let Some(ind) = (0..program.methods.len()).find(|&i| program.methods[i].name.name != "main" && self.hit()) {
+                program.methods.remove(ind);
+                return;
+            }
+            if let Some(ind) = (0..program.imports.len()).find(|_| self.hit()) {
+                program.imports.remove(ind);
+                return;
+            }
+            self.reduce_fields(&mut program.fields);
+            if self.applied() {
+                return;
+            }
+        }
+        for method in program.methods.iter_mut() {
+            self.reduce_block(&mut method.body);
+            if self.applied() {
+                return;
+            }
+        }
+    }
+
+    fn reduce_fields(&mut self, fields: &mut Vec<Box<AST::FieldDecl>>) {
+        for field_ind in 0..fields.len() {
+            for var_ind in 0..fields[field_ind].vars.len() {
+                if self.hit() {
+                    fields[field_ind].vars.remove(var_ind);
+                    if fields[field_ind].vars.is_empty() {
+                        fields.remove(field_ind);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn reduce_block(&mut self, block: &mut AST::Block) {
+        match self.kind {
+            ReductionKind::Declarations => {
+                self.reduce_fields(&mut block.fields);
+                if self.applied() {
+                    return;
+                }
+            },
+            ReductionKind::Statements => {
+                for ind in 0..block.statements.len() {
+                    if self.hit() {
+                        block.statements.remove(ind);
+                        return;
+                    }
+                    if let Some(replacement) = self.statement_replacement(&mut block.statements[ind]) {
+                        block.statements.splice(ind..ind + 1, replacement);
+                        return;
+                    }
+                    if self.applied() {
+                        return;
+                    }
+                }
+            },
+            ReductionKind::Expressions => (),
+        }
+        for statement in block.statements.iter_mut() {
+            self.reduce_statement(statement);
+            if self.applied() {
+                return;
+            }
+        }
+    }
+
+    // reductions that replace a compound statement with the statements of one of its blocks
+    fn statement_replacement(&mut self, statement: &mut AST::ASTNode) -> Option<Vec<Box<AST::ASTNode>>> {
+        // blocks declaring fields can not be spliced into their parent
+        let splice = |block: &AST::Block| if block.fields.is_empty() { Some(block.statements.clone()) } else { None };
+        match statement {
+            AST::ASTNode::IfStatement(if_statement) => {
+                if let Some(then_statements) = splice(&if_statement.then_block) {
+                    if self.hit() {
+                        return Some(then_statements);
+                    }
+                }
+                if let Some(else_block) = if_statement.else_block.as_ref() {
+                    if let Some(else_statements) = splice(else_block) {
+                        if self.hit() {
+                            return Some(else_statements);
+                        }
+                    }
+                    if self.hit() {
+                        if_statement.else_block = Box::new(None);
+                    }
+                }
+                None
+            },
+            AST::ASTNode::ForStatement(for_statement) => {
+                splice(&for_statement.block).filter(|_| self.hit())
+            },
+            AST::ASTNode::WhileStatement(while_statement) => {
+                splice(&while_statement.block).filter(|_| self.hit())
+            },
+            _ => None,
+        }
+    }
+
+    fn reduce_statement(&mut self, statement: &mut AST::ASTNode) {
+        match statement {
+            AST::ASTNode::IfStatement(if_statement) => {
+                self.reduce_expression(&mut if_statement.condition);
+                if !self.applied() {
+                    self.reduce_block(&mut if_statement.then_block);
+                }
+                if let Some(else_block) = if_statement.else_block.as_mut() {
+                    if !self.applied() {
+                        self.reduce_block(else_block);
+                    }
+                }
+            },
+            AST::ASTNode::ForStatement(for_statement) => {
+                if let Some(start_expr) = for_statement.start_assignment.expr.as_mut() {
+                    self.reduce_expression(start_expr);
+                }
+                if !self.applied() {
+                    self.reduce_expression(&mut for_statement.end_expr);
+                }
+                if !self.applied() {
+                    self.reduce_block(&mut for_statement.block);
+                }
+            },
+            AST::ASTNode::WhileStatement(while_statement) => {
+                self.reduce_expression(&mut while_statement.condition);
+                if !self.applied() {
+                    self.reduce_block(&mut while_statement.block);
+                }
+            },
+            AST::ASTNode::ReturnStatement(return_statement) => {
+                if let Some(expr) = return_statement.expr.as_mut() {
+                    self.reduce_expression(expr);
+                }
+            },
+            AST::ASTNode::Assignment(assignment) => {
+                if let AST::ASTNode::IndexExpression(index_expression) = assignment.assign_var.as_mut() {
+                    self.reduce_expression(&mut index_expression.idx_expr);
+                }
+                if let Some(expr) = assignment.expr.as_mut() {
+                    if !self.applied() {
+                        self.reduce_expression(expr);
+                    }
+                }
+            },
+            AST::ASTNode::MethodCall(method_call) => {
+                self.reduce_args(&mut method_call.args);
+            },
+            _ => (),
+        }
+    }
+
+    fn reduce_args(&mut self, args: &mut [Box<AST::ASTNode>]) {
+        for arg in args.iter_mut() {
+            self.reduce_expression(arg);
+            if self.applied() {
+                return;
+            }
+        }
+    }
+
+    fn reduce_expression(&mut self, expr: &mut AST::ASTNode) {
+        if self.kind != ReductionKind::Expressions {
+            return;
+        }
+        // candidate replacements for this node, simplest first
+        let mut replacements: Vec<AST::ASTNode> = vec![];
+        match expr {
+            AST::ASTNode::BinaryExpression(binary_expression) => {
+                replacements.push(binary_expression.left_expr.as_ref().clone());
+                replacements.push(binary_expression.right_expr.as_ref().clone());
+            },
+            AST::ASTNode::UnaryExpression(unary_expression) => replacements.push(unary_expression.expr.as_ref().clone()),
+            AST::ASTNode::IntCast(int_cast) => replacements.push(int_cast.cast_expr.as_ref().clone()),
+            AST::ASTNode::LongCast(long_cast) => replacements.push(long_cast.cast_expr.as_ref().clone()),
+            _ => (),
+        }
+        match expr {
+            AST::ASTNode::StringConstant(_) | AST::ASTNode::BoolConstant(_) | AST::ASTNode::CharConstant(_) => (),
+            AST::ASTNode::IntConstant(int_constant) if int_constant.value == "0" => (),
+            AST::ASTNode::LongConstant(long_constant) if long_constant.value == "0" => (),
+            _ => {
+                replacements.push(AST::ASTNode::IntConstant(AST::IntConstant {
+                    is_neg: false,
+                    value: "0".to_string(),
+                    node_id: AST::new_node_id(),
+                }));
+                replacements.push(AST::ASTNode::BoolConstant(AST::BoolConstant {
+                    value: false,
+                    node_id: AST::new_node_id(),
+                }));
+            },
+        }
+        for replacement in replacements {
+            if self.hit() {
+                *expr = replacement;
+                return;
+            }
+        }
+
+        match expr {
+            AST::ASTNode::BinaryExpression(binary_expression) => {
+                self.reduce_expression(&mut binary_expression.left_expr);
+                if !self.applied() {
+                    self.reduce_expression(&mut binary_expression.right_expr);
+                }
+            },
+            AST::ASTNode::UnaryExpression(unary_expression) => self.reduce_expression(&mut unary_expression.expr),
+            AST::ASTNode::IntCast(int_cast) => self.reduce_expression(&mut int_cast.cast_expr),
+            AST::ASTNode::LongCast(long_cast) => self.reduce_expression(&mut long_cast.cast_expr),
+            AST::ASTNode::IndexExpression(index_expression) => self.reduce_expression(&mut index_expression.idx_expr),
+            AST::ASTNode::MethodCall(method_call) => self.reduce_args(&mut method_call.args),
+            _ => (),
+        }
+    }
+}
+
+// apply reduction number target of the given kind, None once target is past the last site
+fn apply_reduction(program: &AST::Program, kind: ReductionKind, target: usize) -> Option<AST::Program> {
+    let mut reduced = program.clone();
+    let mut site = ReductionSite {
+        kind: kind,
+        target: target,
+        count: 0,
+    };
+    site.reduce_program(&mut reduced);
+    if site.applied() {
+        Some(reduced)
+    } else {
+        None
+    }
+}
+
+fn print_program(program: &AST::Program) -> String {
+    let mut printer = ParserPrinter::new();
+    program.accept(&mut printer);
+    printer.output
+}
+
+struct Reducer {
+    command: String,
+    candidate_path: PathBuf,
+    tests_run: usize,
+    debug: bool,
+}
+
+impl Reducer {
+    // runs the interestingness command on source, substituting {} with the file path or appending it
+    fn is_interesting(&mut self, source: &str) -> bool {
+        std::fs::write(&self.candidate_path, source).expect("Failed to write candidate file");
+        self.tests_run += 1;
+        let path = self.candidate_path.display().to_string();
+        let command = if self.command.contains("{}") {
+            self.command.replace("{}", &path)
+        } else {
+            format!("{} {}", self.command, path)
+        };
+        match std::process::Command::new("sh").arg("-c").arg(&command).output() {
+            Ok(output) => output.status.success(),
+            Err(e) => {
+                eprintln!("Failed to run interestingness command: {}", e);
+                false
+            }
+        }
+    }
+
+    fn reduce(&mut self, mut best: AST::Program) -> AST::Program {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for kind in REDUCTION_KINDS {
+                // sites before target were already tried on the current program, so only move forward
+                let mut target = 0;
+                while let Some(candidate) = apply_reduction(&best, kind, target) {
+                    if self.is_interesting(&print_program(&candidate)) {
+                        if self.debug {
+                            eprintln!("Reducer: applied {:?} reduction {}", kind, target);
+                        }
+                        best = candidate;
+                        changed = true;
+                    } else {
+                        target += 1;
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+pub fn reduce(input: &Path, command: Option<String>, mut writer: Box<dyn std::io::Write>, debug: bool) {
+    let command = match command {
+        Some(command) => command,
+        None => {
+            eprintln!("Error: reduce requires an interestingness command given with --interesting.");
+            std::process::exit(1);
+        }
+    };
+    let program = match parse_file(input) {
+        Ok(program) => program,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            eprintln!("Error: the reducer works on the AST, so the input must parse.");
+            std::process::exit(1);
+        }
+    };
+
+    let candidate_dir = std::env::temp_dir().join(format!("decaf-reduce-{}", std::process::id()));
+    std::fs::create_dir_all(&candidate_dir).expect("Failed to create reducer directory");
+    let mut reducer = Reducer {
+        command: command,
+        candidate_path: candidate_dir.join(input.file_name().unwrap_or("candidate.dcf".as_ref())),
+        tests_run: 0,
+        debug: debug,
+    };
+
+    let original_source = print_program(&program);
+    if !reducer.is_interesting(&original_source) {
+        eprintln!("Error: the input is not interesting, the command must exit 0 on it before reducing.");
+        std::fs::remove_dir_all(&candidate_dir).ok();
+        std::process::exit(1);
+    }
+
+    let reduced = reducer.reduce(program);
+    let reduced_source = print_program(&reduced);
+    std::fs::remove_dir_all(&candidate_dir).ok();
+    if debug {
+        eprintln!("Reducer: {} bytes -> {} bytes after {} tests", original_source.len(), reduced_source.len(), reducer.tests_run);
+    }
+    write!(writer, "{}", reduced_source).unwrap();
+}
@@ -19,6 +19,13 @@ impl Block {
         }
     }
 
+    pub fn get_successors(&self) -> Vec<usize> {
+        match self {
+            Block::Condition(block) => block.true_block.into_iter().chain(block.false_block).collect(),
+            _ => self.get_next_block().into_iter().collect(),
+        }
+    }
+
     pub fn set_next_block(&mut self, ind: usize) {
         match self {
             Block::Basic(block) => block.next_block = Some(ind),
@@ -51,6 +58,7 @@ pub struct BasicBlock {
 #[derive(Debug)]
 pub struct ConditionBlock {
     pub cond_expr: Box<AST::ASTNode>,
+    pub line_num: usize, // source line of the if, for or while keyword
     pub true_block: Option<usize>,
     pub false_block: Option<usize>,
 }
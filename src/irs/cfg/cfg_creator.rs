@@ -1,5 +1,7 @@
 use super::super::super::parser::AST;
 use super::super::super::parser::visitor::Visitor;
+use super::super::super::parser::parser_printer::ParserPrinter;
+use super::super::super::semantics::semantics::AnalyzedProgram;
 use super::cfg_blocks::{BasicBlock, Block, ConditionBlock, DeclBlock, NoOp};
 use std::collections::HashMap;
 
@@ -15,17 +17,92 @@ pub struct ControlFlowGraph {
     pub nodes: Vec<Block>,
     pub start_block: usize,
     pub end_block: usize, // this varies by true and false branch end block
+    pub exit_block: usize, // no op every return and the end of the method body lead to
+    pub update_blocks: Vec<usize>, // for loop updates, only reached through the end of the body or a continue
 
     // flags
-    pub prev_loop_blocks: Vec<usize>, // stack of loop blocks
+    pub prev_loop_blocks: Vec<LoopJumps>, // stack of loops the current statement is nested in
+    pub return_blocks: Vec<usize>, // blocks ending in a return, linked to the exit block once it exists
+    pub after_jump: bool, // the last block ended in a return, break or continue so the next block is not linked to it
+}
+
+// blocks ending in a break or continue, linked once the loop's exit and continue target exist
+#[derive(Debug)]
+#[derive(Default)]
+pub struct LoopJumps {
+    pub breaks: Vec<usize>,
+    pub continues: Vec<usize>,
+}
+
+impl ProgramGraph {
+    // textual dump of every method graph, methods sorted by name so the output is deterministic
+    pub fn dump(&self) -> String {
+        let mut method_names: Vec<&String> = self.method_graphs.keys().collect();
+        method_names.sort();
+        let mut output = String::new();
+        for method_name in method_names {
+            output.push_str(&format!("method {}\n", method_name));
+            output.push_str(&self.method_graphs[method_name].dump());
+        }
+        return output;
+    }
 }
 
 impl ControlFlowGraph {
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        for (i, block) in self.nodes.iter().enumerate() {
+            let kind = match block {
+                Block::Basic(_) => "basic",
+                Block::Condition(_) => "condition",
+                Block::NoOp(_) => "noop",
+                Block::Decl(_) => "decl",
+            };
+            let successors: Vec<String> = block.get_successors().iter().map(|succ| format!("B{}", succ)).collect();
+            let start = if i == self.start_block { " (start)" } else { "" };
+            output.push_str(&format!("  B{}{} {} -> [{}]\n", i, start, kind, successors.join(", ")));
+
+            // contents printed as source, one line per statement or declaration
+            let mut printer = ParserPrinter::new();
+            match block {
+                Block::Basic(basic_block) => {
+                    for statement in &basic_block.statements {
+                        statement.accept(&mut printer);
+                        // the printer leaves the semicolon of call statements to the enclosing block
+                        if let AST::ASTNode::MethodCall(_) = statement.as_ref() {
+                            printer.output.push(';');
+                        }
+                        printer.output.push('\n');
+                    }
+                },
+                Block::Condition(cond_block) => cond_block.cond_expr.accept(&mut printer),
+                Block::NoOp(_) => (),
+                Block::Decl(decl_block) => {
+                    for decl in &decl_block.decls {
+                        decl.accept(&mut printer);
+                    }
+                },
+            }
+            for line in printer.output.lines().filter(|line| !line.trim().is_empty()) {
+                output.push_str(&format!("    {}\n", line));
+            }
+        }
+        return output;
+    }
 }
 
 impl Visitor for ControlFlowGraph { 
     fn visit_method_decl(&mut self, method_decl: &AST::MethodDecl) {
         self.visit_block(&method_decl.body);
+
+        // the end of the body falls through to the exit, returns jump to it
+        self.add_block(Block::NoOp(NoOp {
+            next_block: None,
+        }));
+        self.exit_block = self.end_block;
+        for return_block in self.return_blocks.clone() {
+            self.nodes[return_block].set_next_block(self.exit_block);
+        }
     }
 
     fn visit_block(&mut self, block: &AST::Block) {
@@ -38,7 +115,6 @@ impl Visitor for ControlFlowGraph {
         self.add_block(Block::Decl(method_field_block));
         let mut block_statements: Vec<Box<AST::ASTNode>> = vec![];
 
-        // TODO: this logic will be modified as we need to draw back edges to previous nodes
         for statement in &block.statements {
             match statement.as_ref() {
                 AST::ASTNode::IfStatement(if_statement) => {
@@ -65,7 +141,25 @@ impl Visitor for ControlFlowGraph {
                     self.visit_for_statement(for_statement);
                     block_statements = vec![];
                 },
-                AST::ASTNode::StatementControl(statement_control) => self.visit_statement_control(statement_control),
+                AST::ASTNode::ReturnStatement(return_statement) => {
+                    // the jump ends the basic block, anything after it starts an unreachable one
+                    block_statements.push(statement.clone());
+                    self.add_block(Block::Basic( BasicBlock {
+                        statements: block_statements.clone(),
+                        next_block: None,
+                    }));
+                    self.visit_return_statement(return_statement);
+                    block_statements = vec![];
+                },
+                AST::ASTNode::StatementControl(statement_control) => {
+                    block_statements.push(statement.clone());
+                    self.add_block(Block::Basic( BasicBlock {
+                        statements: block_statements.clone(),
+                        next_block: None,
+                    }));
+                    self.visit_statement_control(statement_control);
+                    block_statements = vec![];
+                },
                 _ => {
                     block_statements.push(statement.clone());
                 },
@@ -78,12 +172,11 @@ impl Visitor for ControlFlowGraph {
     }
     
     fn visit_if_statement(&mut self, if_statement: &AST::IfStatement) {
-        self.visit_expression(if_statement.condition.as_ref());
+        self.add_condition_block(if_statement.condition.as_ref(), if_statement.line_num);
         let cond_block_ind = self.nodes.len() - 1;
         let then_block_start_ind = self.nodes.len(); 
         self.visit_block(if_statement.then_block.as_ref());
         let then_block_end_ind = self.nodes.len() - 1; 
-        self.nodes[cond_block_ind].set_branch_block(then_block_start_ind, true);        
 
         // set then branch pointers
         self.nodes[cond_block_ind].set_branch_block(then_block_start_ind, true);
@@ -104,41 +197,62 @@ impl Visitor for ControlFlowGraph {
         self.nodes[then_block_end_ind].set_next_block(end_ind);
         if if_statement.else_block.is_some() {
             self.nodes[else_block_end_ind].set_next_block(end_ind);
-        } 
+        } else {
+            // no else branch so false branch falls through to the merge block
+            self.nodes[cond_block_ind].set_branch_block(end_ind, false);
+        }
     }
 
     fn visit_while_statement(&mut self, while_statement: &AST::WhileStatement) {
-        
+        self.add_condition_block(while_statement.condition.as_ref(), while_statement.line_num);
+        let cond_block_ind = self.end_block;
+        self.prev_loop_blocks.push(LoopJumps::default());
+        self.visit_block(while_statement.block.as_ref());
+        let body_end_ind = self.end_block;
+        self.nodes[cond_block_ind].set_branch_block(cond_block_ind + 1, true);
+
+        // back edge from the end of the body
+        self.nodes[body_end_ind].set_next_block(cond_block_ind);
+        self.close_loop(cond_block_ind, cond_block_ind);
     }
 
     fn visit_for_statement(&mut self, for_statement: &AST::ForStatement) {
-        
-    }
+        self.add_block(Block::Basic( BasicBlock {
+            statements: vec![Box::new(AST::ASTNode::Assignment(for_statement.start_assignment.as_ref().clone()))],
+            next_block: None,
+        }));
+        self.add_condition_block(for_statement.end_expr.as_ref(), for_statement.line_num);
+        let cond_block_ind = self.end_block;
+        self.prev_loop_blocks.push(LoopJumps::default());
+        self.visit_block(for_statement.block.as_ref());
+        self.nodes[cond_block_ind].set_branch_block(cond_block_ind + 1, true);
 
-    fn visit_return_statement(&mut self, _return_statement: &AST::ReturnStatement) {
-        
+        // the update follows the body and continues, then loops back to the condition
+        self.add_block(Block::Basic( BasicBlock {
+            statements: vec![for_statement.update_expr.clone()],
+            next_block: None,
+        }));
+        let update_block_ind = self.end_block;
+        self.update_blocks.push(update_block_ind);
+        self.nodes[update_block_ind].set_next_block(cond_block_ind);
+        self.close_loop(cond_block_ind, update_block_ind);
     }
 
-    fn visit_statement_control(&mut self, _statement_control: &AST::StatementControl) {
-        
+    fn visit_return_statement(&mut self, _return_statement: &AST::ReturnStatement) {
+        self.return_blocks.push(self.end_block);
+        self.after_jump = true;
     }
 
-    fn visit_expression(&mut self, expression: &AST::ASTNode) {
-        match expression {
-            // AST::ASTNode::BinaryExpression(binary_expression) => {
-            //     self.visit_binary_expression(binary_expression);
-            // },
-            _ => {
-                // create condition expression
-                let condition_block = ConditionBlock {
-                    cond_expr: Box::new(expression.clone()),
-                    true_block: None,
-                    false_block: None,
-                };
-                self.add_block(Block::Condition(condition_block));
-            },
+    fn visit_statement_control(&mut self, statement_control: &AST::StatementControl) {
+        let jump_block_ind = self.end_block;
+        if let Some(loop_jumps) = self.prev_loop_blocks.last_mut() {
+            if statement_control.op == "break" {
+                loop_jumps.breaks.push(jump_block_ind);
+            } else {
+                loop_jumps.continues.push(jump_block_ind);
+            }
         }
-        
+        self.after_jump = true;
     }
 }
 
@@ -147,14 +261,44 @@ impl ControlFlowGraph {
     fn add_block(&mut self, block: Block) {
         self.nodes.push(block);
         let latest_node_ind = self.nodes.len() - 1;
-        if self.nodes.len() > 0 {
+        if self.nodes.len() > 1 && !self.after_jump {
             self.nodes[self.end_block].set_next_block(latest_node_ind);
         }
+        self.after_jump = false;
         self.end_block = latest_node_ind;
     }
+
+    fn add_condition_block(&mut self, expression: &AST::ASTNode, line_num: usize) {
+        let condition_block = ConditionBlock {
+            cond_expr: Box::new(expression.clone()),
+            line_num: line_num,
+            true_block: None,
+            false_block: None,
+        };
+        self.add_block(Block::Condition(condition_block));
+    }
+
+    // adds the loop's exit block, the target of the false branch and breaks, and links continues to continue_ind
+    fn close_loop(&mut self, cond_block_ind: usize, continue_ind: usize) {
+        self.nodes.push(Block::NoOp(NoOp {
+            next_block: None,
+        }));
+        let exit_ind = self.nodes.len() - 1;
+        self.end_block = exit_ind;
+        self.nodes[cond_block_ind].set_branch_block(exit_ind, false);
+
+        let loop_jumps = self.prev_loop_blocks.pop().unwrap_or_default();
+        for break_block in loop_jumps.breaks {
+            self.nodes[break_block].set_next_block(exit_ind);
+        }
+        for continue_block in loop_jumps.continues {
+            self.nodes[continue_block].set_next_block(continue_ind);
+        }
+    }
 }
 
-pub fn construct_program_graph(ast: AST::Program) -> ProgramGraph {
+pub fn construct_program_graph(analyzed_program: &AnalyzedProgram) -> ProgramGraph {
+    let ast = &analyzed_program.ast;
     let mut program_graph = ProgramGraph {
         import_decl: vec![],
         global_field_decls: vec![],
@@ -164,13 +308,17 @@ pub fn construct_program_graph(ast: AST::Program) -> ProgramGraph {
     program_graph.global_field_decls = ast.fields.clone();
 
     // create cfgs for all methods
-    for method_decl in ast.methods {
+    for method_decl in &ast.methods {
         let method_name = method_decl.name.name.clone();
         let mut cfg = ControlFlowGraph {
             nodes: vec![],
             start_block: 0,
             end_block: 0,
+            exit_block: 0,
+            update_blocks: vec![],
             prev_loop_blocks: vec![],
+            return_blocks: vec![],
+            after_jump: false,
         };
         cfg.visit_method_decl(method_decl.as_ref());
         program_graph.method_graphs.insert(method_name, Box::new(cfg));
@@ -1,2 +1,3 @@
 pub mod cfg_creator;
 pub mod cfg_blocks;
+pub mod reachability;
@@ -0,0 +1,202 @@
+use super::super::super::parser::AST;
+use super::cfg_blocks::Block;
+use super::cfg_creator::ControlFlowGraph;
+
+/*
+Reachability questions over a method's control flow graph. Conditions that are the constant true
+never take their false branch, so the code after an infinite loop is unreachable and an
+if (true) { return ...; } counts as returning. No other constants are folded.
+*/
+
+fn is_bool_constant(expr: &AST::ASTNode, value: bool) -> bool {
+    match expr {
+        AST::ASTNode::BoolConstant(bool_constant) => bool_constant.value == value,
+        _ => false,
+    }
+}
+
+// successors that can be taken at run time
+fn live_successors(block: &Block) -> Vec<usize> {
+    match block {
+        Block::Condition(cond_block) if is_bool_constant(&cond_block.cond_expr, true) => cond_block.true_block.into_iter().collect(),
+        _ => block.get_successors(),
+    }
+}
+
+pub fn reachable_blocks(cfg: &ControlFlowGraph) -> Vec<bool> {
+    let mut reached = vec![false; cfg.nodes.len()];
+    let mut stack = vec![cfg.start_block];
+    while let Some(ind) = stack.pop() {
+        if ind >= cfg.nodes.len() || reached[ind] {
+            continue;
+        }
+        reached[ind] = true;
+        stack.extend(live_successors(&cfg.nodes[ind]));
+    }
+    return reached;
+}
+
+// source line of the first statement in a block, None for blocks without code
+fn block_line(cfg: &ControlFlowGraph, ind: usize) -> Option<usize> {
+    match &cfg.nodes[ind] {
+        // a for loop update is only skipped because the body never finishes, which is not a mistake in the update
+        Block::Basic(_) if cfg.update_blocks.contains(&ind) => None,
+        Block::Basic(basic_block) => basic_block.statements.first().and_then(|statement| statement.line_num()),
+        Block::Condition(cond_block) => Some(cond_block.line_num),
+        Block::NoOp(_) | Block::Decl(_) => None,
+    }
+}
+
+/*
+Line of the first statement of every unreachable region. A region starts at a block no other block
+can jump to (the code after a return, break, continue or infinite loop) and covers the unreachable
+blocks it leads to, so a dead loop or if is reported once rather than once per block.
+*/
+pub fn unreachable_lines(cfg: &ControlFlowGraph) -> Vec<usize> {
+    let reached = reachable_blocks(cfg);
+    let mut has_predecessor = vec![false; cfg.nodes.len()];
+    for block in &cfg.nodes {
+        for succ in live_successors(block) {
+            if succ < cfg.nodes.len() {
+                has_predecessor[succ] = true;
+            }
+        }
+    }
+
+    let mut covered = vec![false; cfg.nodes.len()];
+    let mut lines: Vec<usize> = vec![];
+    for root in 0..cfg.nodes.len() {
+        if reached[root] || has_predecessor[root] || covered[root] {
+            continue;
+        }
+        let mut region: Vec<usize> = vec![];
+        let mut stack = vec![root];
+        while let Some(ind) = stack.pop() {
+            if ind >= cfg.nodes.len() || reached[ind] || covered[ind] {
+                continue;
+            }
+            covered[ind] = true;
+            region.push(ind);
+            stack.extend(live_successors(&cfg.nodes[ind]));
+        }
+        // blocks are numbered in source order, so the lowest numbered block with code comes first
+        region.sort();
+        if let Some(line) = region.iter().find_map(|ind| block_line(cfg, *ind)) {
+            lines.push(line);
+        }
+    }
+    lines.sort();
+    lines.dedup();
+    return lines;
+}
+
+// lines of the while and for loops whose condition is the constant false
+pub fn constant_false_loop_lines(cfg: &ControlFlowGraph) -> Vec<usize> {
+    let mut lines: Vec<usize> = vec![];
+    for (ind, block) in cfg.nodes.iter().enumerate() {
+        if let Block::Condition(cond_block) = block {
+            // only loop conditions are the target of a back edge
+            let is_loop = cfg.nodes.iter().skip(ind + 1).any(|later| later.get_successors().contains(&ind));
+            if is_loop && is_bool_constant(&cond_block.cond_expr, false) {
+                lines.push(cond_block.line_num);
+            }
+        }
+    }
+    return lines;
+}
+
+// whether a reachable path gets to the end of the method body without a return
+pub fn falls_off_end(cfg: &ControlFlowGraph) -> bool {
+    let reached = reachable_blocks(cfg);
+    for (ind, block) in cfg.nodes.iter().enumerate() {
+        if !reached[ind] || !live_successors(block).contains(&cfg.exit_block) {
+            continue;
+        }
+        let ends_in_return = match block {
+            Block::Basic(basic_block) => matches!(basic_block.statements.last().map(|statement| statement.as_ref()), Some(AST::ASTNode::ReturnStatement(_))),
+            _ => false,
+        };
+        if !ends_in_return {
+            return true;
+        }
+    }
+    return false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{constant_false_loop_lines, falls_off_end, reachable_blocks, unreachable_lines};
+    use crate::irs::cfg::cfg_creator::{construct_program_graph, ControlFlowGraph};
+    use crate::parser::parser::parse_source;
+    use crate::semantics::semantics::analyze_program;
+
+    // graph of the method f in a program, analyzed without lints so dead code is not rejected
+    fn method_graph(src: &str) -> ControlFlowGraph {
+        let ast = parse_source(src).expect("test program should parse");
+        let analyzed_program = analyze_program(ast, false).expect("test program should pass semantic checks");
+        let mut program_graph = construct_program_graph(&analyzed_program);
+        return *program_graph.method_graphs.remove("f").expect("test program should define f");
+    }
+
+    #[test]
+    fn if_else_returning_on_both_branches() {
+        let cfg = method_graph("int f(int x) {\n if (x > 0) {\n return 1;\n } else {\n return 2;\n }\n x = 3;\n}\nvoid main() { }");
+        assert!(!falls_off_end(&cfg));
+        assert_eq!(unreachable_lines(&cfg), vec![7]);
+    }
+
+    #[test]
+    fn if_without_else_falls_through() {
+        let cfg = method_graph("int f(int x) {\n if (x > 0) {\n return 1;\n }\n}\nvoid main() { }");
+        assert!(falls_off_end(&cfg));
+        assert!(unreachable_lines(&cfg).is_empty());
+    }
+
+    #[test]
+    fn infinite_loop_with_break() {
+        let cfg = method_graph("int f(int x) {\n while (true) {\n if (x > 0) {\n break;\n }\n x = x + 1;\n }\n return x;\n}\nvoid main() { }");
+        assert!(!falls_off_end(&cfg));
+        assert!(unreachable_lines(&cfg).is_empty());
+    }
+
+    #[test]
+    fn infinite_loop_without_break() {
+        let cfg = method_graph("int f(int x) {\n while (true) {\n x = x + 1;\n }\n x = 2;\n}\nvoid main() { }");
+        assert!(!falls_off_end(&cfg));
+        assert_eq!(unreachable_lines(&cfg), vec![5]);
+    }
+
+    #[test]
+    fn continue_reaches_the_for_update() {
+        let cfg = method_graph("void f() {\n int i;\n for (i = 0; i < 10; i++) {\n if (i == 3) {\n continue;\n }\n i = i + 1;\n }\n}\nvoid main() { }");
+        assert!(falls_off_end(&cfg));
+        assert!(unreachable_lines(&cfg).is_empty());
+
+        // a body that always continues only gets to the update through the continue
+        let cfg = method_graph("void f() {\n int i;\n for (i = 0; i < 10; i++) {\n continue;\n }\n}\nvoid main() { }");
+        let reached = reachable_blocks(&cfg);
+        assert_eq!(cfg.update_blocks.len(), 1);
+        assert!(reached[cfg.update_blocks[0]]);
+    }
+
+    #[test]
+    fn for_update_after_returning_body_is_not_reported() {
+        let cfg = method_graph("int f() {\n int i;\n for (i = 0; i < 10; i++) {\n return i;\n }\n return 0;\n}\nvoid main() { }");
+        assert!(!falls_off_end(&cfg));
+        assert!(unreachable_lines(&cfg).is_empty());
+        assert!(!reachable_blocks(&cfg)[cfg.update_blocks[0]]);
+    }
+
+    #[test]
+    fn code_after_return_is_reported_once() {
+        let cfg = method_graph("int f(int x) {\n return x;\n x = 1;\n x = 2;\n if (x > 0) {\n x = 3;\n }\n}\nvoid main() { }");
+        assert!(!falls_off_end(&cfg));
+        assert_eq!(unreachable_lines(&cfg), vec![3]);
+    }
+
+    #[test]
+    fn constant_false_loops_are_found() {
+        let cfg = method_graph("void f(int x) {\n while (false) {\n x = 1;\n }\n if (false) {\n x = 2;\n }\n while (x > 0) {\n x = x - 1;\n }\n}\nvoid main() { }");
+        assert_eq!(constant_false_loop_lines(&cfg), vec![2]);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod cfg;
 pub mod three_address;
+pub mod verifier;
@@ -1,14 +1,17 @@
 use super::super::super::parser::visitor::{Visitor};
 use super::instructions::{Instruction, ArrayInstruction, Call, InstructionType, PushInstruction, FlowInstruction, UnaryInstruction, BinaryInstruction, Ret};
 use super::super::super::parser::AST;
-use super::super::super::semantics::symbol_table::{Entry, ArrayEntry, VarEntry, MethodEntry, ImportEntry, Table};
+use super::super::super::semantics::symbol_table::{Entry, ArrayEntry, VarEntry, MethodEntry, ImportEntry, Table, Type};
+use super::super::super::semantics::semantics::AnalyzedProgram;
 use std::collections::HashMap;
 
 pub struct ThreeAddressCode {
-    var_entries: Vec<Entry>, // vector of entry variables that can be referenced
-    global_instructions: Vec<Box<Instruction>>,
-    func_instructions: HashMap<String, Vec<Box<Instruction>>>,
+    pub var_entries: Vec<Entry>, // vector of entry variables that can be referenced
+    pub global_instructions: Vec<Box<Instruction>>,
+    pub func_instructions: HashMap<String, Vec<Box<Instruction>>>,
     scopes: Vec<Box<Table>>,
+    expr_types: HashMap<usize, Type>, // expression node id -> type, picks 32 or 64 bit operations
+    resolutions: HashMap<usize, Entry>, // identifier node id -> declaration, disambiguates shadowed names
 
     // flags
     is_global: bool,
@@ -98,15 +101,17 @@ impl Visitor for ThreeAddressCode {
     fn visit_char_constant(&mut self, _char_constant: &AST::CharConstant) {}   
 }
 
-pub fn compile_three_address(ast: AST::Program) -> ThreeAddressCode {
+pub fn compile_three_address(analyzed_program: &AnalyzedProgram) -> ThreeAddressCode {
     let mut tac = ThreeAddressCode {
         var_entries: Vec::new(),
         global_instructions: Vec::new(),
         func_instructions: HashMap::new(),
-        scopes: Vec::new(),
+        scopes: analyzed_program.scopes.clone(),
+        expr_types: analyzed_program.expr_types.clone(),
+        resolutions: analyzed_program.resolutions.clone(),
         is_global: false,
     };
 
-    tac.visit_program(&ast);
+    tac.visit_program(&analyzed_program.ast);
     return tac;
 }
\ No newline at end of file
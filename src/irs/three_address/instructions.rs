@@ -96,6 +96,29 @@ pub enum Instruction {
     Ret(Ret),
 }
 
+impl Instruction {
+    // variable ids (indices into var_entries) referenced by this instruction
+    pub fn get_var_ids(&self) -> Vec<usize> {
+        match self {
+            Instruction::Binary(instr) => vec![instr.var1, instr.var2],
+            Instruction::Unary(instr) => vec![instr.target, instr.var],
+            Instruction::Push(instr) => vec![instr.var],
+            Instruction::Flow(_) => vec![],
+            Instruction::Array(instr) => vec![instr.target, instr.var, instr.ind],
+            Instruction::Call(instr) => vec![instr.func_var],
+            Instruction::Ret(_) => vec![],
+        }
+    }
+
+    // label a Goto jumps to, or label a Label instruction defines
+    pub fn get_flow_label(&self) -> Option<(InstructionType, &str)> {
+        match self {
+            Instruction::Flow(instr) => Some((instr.instruction.clone(), instr.var.as_str())),
+            _ => None,
+        }
+    }
+}
+
 pub trait InstructionTrait {
     fn get_type(&self) -> InstructionType;
 }
@@ -0,0 +1,154 @@
+use super::super::cfg::cfg_blocks::Block;
+use super::super::cfg::cfg_creator::{ControlFlowGraph, ProgramGraph};
+use super::super::three_address::compiler::ThreeAddressCode;
+use super::super::three_address::instructions::{Instruction, InstructionType};
+use std::collections::HashSet;
+
+/*
+Checks structural invariants of the three address code and the method CFGs.
+Errors are broken invariants, warnings are suspicious but legal (e.g. unreachable blocks).
+*/
+pub struct IRVerifier {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl IRVerifier {
+    fn push_error(&mut self, error: &str) {
+        self.errors.push(format!("IR Verifier: {}", error));
+    }
+
+    fn push_warning(&mut self, warning: &str) {
+        self.warnings.push(format!("IR Verifier: {}", warning));
+    }
+
+    fn verify_instructions(&mut self, func_name: &str, instructions: &[Box<Instruction>], var_count: usize) {
+        // every variable id must refer to a valid var_entries slot
+        for (i, instruction) in instructions.iter().enumerate() {
+            for var_id in instruction.get_var_ids() {
+                if var_id >= var_count {
+                    self.push_error(&format!("{} instruction {} - variable id {} is out of range of var_entries (len {}).", func_name, i, var_id, var_count));
+                }
+            }
+        }
+
+        // every goto target must be defined by exactly one label
+        let mut labels: HashSet<&str> = HashSet::new();
+        for instruction in instructions {
+            if let Some((InstructionType::Label, label)) = instruction.get_flow_label() {
+                if !labels.insert(label) {
+                    self.push_error(&format!("{} - label {} is defined more than once.", func_name, label));
+                }
+            }
+        }
+        for (i, instruction) in instructions.iter().enumerate() {
+            if let Some((InstructionType::Goto, label)) = instruction.get_flow_label() {
+                if !labels.contains(label) {
+                    self.push_error(&format!("{} instruction {} - goto target {} does not exist.", func_name, i, label));
+                }
+            }
+        }
+    }
+
+    fn verify_cfg(&mut self, method_name: &str, cfg: &ControlFlowGraph) {
+        let node_count = cfg.nodes.len();
+        if cfg.start_block >= node_count {
+            self.push_error(&format!("{} - start block {} does not exist.", method_name, cfg.start_block));
+            return;
+        }
+
+        for (i, block) in cfg.nodes.iter().enumerate() {
+            if let Block::Condition(cond_block) = block {
+                if cond_block.true_block.is_none() || cond_block.false_block.is_none() {
+                    self.push_error(&format!("{} block {} - condition block is missing a true or false successor.", method_name, i));
+                }
+            }
+            for succ in block.get_successors() {
+                if succ >= node_count {
+                    self.push_error(&format!("{} block {} - successor {} does not exist.", method_name, i, succ));
+                }
+            }
+        }
+
+        // flag blocks not reachable from the start block
+        let mut visited = vec![false; node_count];
+        let mut stack = vec![cfg.start_block];
+        while let Some(ind) = stack.pop() {
+            if visited[ind] {
+                continue;
+            }
+            visited[ind] = true;
+            for succ in cfg.nodes[ind].get_successors() {
+                if succ < node_count && !visited[succ] {
+                    stack.push(succ);
+                }
+            }
+        }
+        for (i, reached) in visited.iter().enumerate() {
+            // blocks without code only join the graph after a return, break or continue, and a for loop
+            // update is skipped whenever the body never finishes, neither is suspicious
+            let has_code = match &cfg.nodes[i] {
+                Block::Basic(basic_block) => !basic_block.statements.is_empty() && !cfg.update_blocks.contains(&i),
+                Block::Decl(decl_block) => !decl_block.decls.is_empty(),
+                Block::Condition(_) => true,
+                Block::NoOp(_) => false,
+            };
+            if !reached && has_code {
+                self.push_warning(&format!("{} block {} - block is unreachable from the start block.", method_name, i));
+            }
+        }
+    }
+}
+
+pub fn verify_ir(three_addr_code: &ThreeAddressCode, program_graph: &ProgramGraph) -> IRVerifier {
+    let mut verifier = IRVerifier {
+        errors: vec![],
+        warnings: vec![],
+    };
+    let var_count = three_addr_code.var_entries.len();
+    verifier.verify_instructions("globals", &three_addr_code.global_instructions, var_count);
+
+    // sort names so verifier output is deterministic
+    let mut func_names: Vec<&String> = three_addr_code.func_instructions.keys().collect();
+    func_names.sort();
+    for func_name in func_names {
+        verifier.verify_instructions(func_name, &three_addr_code.func_instructions[func_name], var_count);
+    }
+
+    let mut method_names: Vec<&String> = program_graph.method_graphs.keys().collect();
+    method_names.sort();
+    for method_name in method_names {
+        verifier.verify_cfg(method_name, &program_graph.method_graphs[method_name]);
+    }
+    verifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify_ir;
+    use crate::irs::cfg::cfg_creator::construct_program_graph;
+    use crate::irs::three_address::compiler::compile_three_address;
+    use crate::parser::parser::parse_source;
+    use crate::semantics::semantics::analyze_program;
+
+    fn warnings(src: &str) -> Vec<String> {
+        let ast = parse_source(src).expect("test program should parse");
+        let analyzed_program = analyze_program(ast, false).expect("test program should pass semantic checks");
+        let verifier = verify_ir(&compile_three_address(&analyzed_program), &construct_program_graph(&analyzed_program));
+        assert!(verifier.errors.is_empty(), "{:?}", verifier.errors);
+        return verifier.warnings;
+    }
+
+    #[test]
+    fn jumps_leave_no_suspicious_blocks() {
+        assert!(warnings("int f(int a) {\n return a;\n}\nvoid main() { f(1); }").is_empty());
+        assert!(warnings("int f(int a) {\n if (a > 0) {\n return 1;\n } else {\n return 2;\n }\n}\nvoid main() { f(1); }").is_empty());
+        assert!(warnings("int f() {\n int i;\n for (i = 0; i < 3; i++) {\n if (i > 1) {\n break;\n }\n continue;\n }\n for (i = 0; i < 3; i++) {\n return i;\n }\n return 0;\n}\nvoid main() { f(); }").is_empty());
+    }
+
+    #[test]
+    fn code_after_a_return_is_unreachable() {
+        let warnings = warnings("int f(int a) {\n return a;\n a = 2;\n}\nvoid main() { f(1); }");
+        assert_eq!(warnings, vec!["IR Verifier: f block 2 - block is unreachable from the start block."]);
+    }
+}
@@ -0,0 +1,138 @@
+/*
+Decaf compiler library. The stage modules keep their file based drivers (used by the command line
+binary); the functions below run the pipeline over source held in memory and never touch the
+filesystem or exit the process, so tools, tests and editor integrations can embed the compiler.
+*/
+pub mod utils;
+pub mod scanner;
+pub mod parser;
+pub mod semantics;
+pub mod irs;
+pub mod assembler;
+pub mod evaluator;
+pub mod fuzzer;
+pub mod testing;
+
+use irs::cfg::cfg_creator::{construct_program_graph, ProgramGraph};
+use irs::three_address::compiler::{compile_three_address, ThreeAddressCode};
+use irs::verifier::verifier::verify_ir;
+use semantics::semantics::{check_program, AnalyzedProgram};
+use semantics::lints::LintConfig;
+use semantics::symbol_table::Occurrence;
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum Stage {
+    Scan,
+    Parse,
+    Semantics,
+    IR,
+}
+
+/*
+Errors reported by the first stage that failed, in the same format the command line prints them
+*/
+#[derive(Debug)]
+pub struct Diagnostics {
+    pub stage: Stage,
+    pub messages: Vec<String>,
+}
+
+pub struct CompileOptions {
+    pub debug: bool,
+    pub verify_ir: bool, // run the IR verifier, its errors fail the compile
+    pub lints: LintConfig, // denied lints fail the compile in the semantics stage
+}
+
+impl Default for CompileOptions {
+    fn default() -> CompileOptions {
+        CompileOptions {
+            debug: false,
+            verify_ir: true,
+            lints: LintConfig::default(),
+        }
+    }
+}
+
+/*
+Everything produced by a successful compile. There is no assembly yet since the backend does not emit code.
+*/
+pub struct Artifacts {
+    pub tokens: Vec<String>,
+    pub program: AnalyzedProgram, // AST and symbol tables
+    pub warnings: Vec<String>, // lints at warn level
+    pub program_graph: ProgramGraph,
+    pub three_address_code: ThreeAddressCode,
+    pub ir_warnings: Vec<String>,
+}
+
+fn diagnostics(stage: Stage, messages: Vec<String>) -> Diagnostics {
+    Diagnostics {
+        stage: stage,
+        messages: messages,
+    }
+}
+
+// tokens in the "line TYPE value" format of the scan target
+pub fn scan(source: &str) -> Result<Vec<String>, Diagnostics> {
+    scanner::scanner::scan_source(source).map_err(|errors| diagnostics(Stage::Scan, errors))
+}
+
+pub fn parse(source: &str) -> Result<parser::AST::Program, Diagnostics> {
+    let tokens = scan(source)?;
+    parser::parser::parse_tokens(tokens).map_err(|errors| diagnostics(Stage::Parse, errors))
+}
+
+// ParserPrinter rendering of the program, what the parse target prints with --debug
+pub fn pretty_print(source: &str) -> Result<String, Diagnostics> {
+    let ast = parse(source)?;
+    let mut printer = parser::parser_printer::ParserPrinter::new();
+    ast.accept(&mut printer);
+    Ok(printer.output)
+}
+
+// parse, type check and lint at the default levels, returning the AST with its symbol tables
+pub fn check(source: &str) -> Result<AnalyzedProgram, Diagnostics> {
+    let ast = parse(source)?;
+    let (program, _) = check_program(ast, &LintConfig::default(), false).map_err(|errors| diagnostics(Stage::Semantics, errors))?;
+    Ok(program)
+}
+
+// every identifier with the declaration it resolves to, sorted by line
+pub fn symbols(source: &str) -> Result<Vec<Occurrence>, Diagnostics> {
+    Ok(check(source)?.occurrences)
+}
+
+// run a checked program with the tree walking evaluator, returning its output and exit code
+pub fn evaluate(source: &str) -> Result<(String, i32), Diagnostics> {
+    let program = check(source)?;
+    Ok(evaluator::evaluator::evaluate_program(program.ast, false))
+}
+
+pub fn compile(source: &str, options: &CompileOptions) -> Result<Artifacts, Diagnostics> {
+    let tokens = scan(source)?;
+    let ast = parser::parser::parse_tokens(tokens.clone()).map_err(|errors| diagnostics(Stage::Parse, errors))?;
+    let (program, warnings) = check_program(ast, &options.lints, options.debug).map_err(|errors| diagnostics(Stage::Semantics, errors))?;
+
+    let program_graph = construct_program_graph(&program);
+    let three_address_code = compile_three_address(&program);
+    let mut ir_warnings = vec![];
+    if options.verify_ir {
+        let verifier = verify_ir(&three_address_code, &program_graph);
+        if !verifier.errors.is_empty() {
+            return Err(diagnostics(Stage::IR, verifier.errors));
+        }
+        ir_warnings = verifier.warnings;
+    }
+
+    Ok(Artifacts {
+        tokens: tokens,
+        program: program,
+        warnings: warnings,
+        program_graph: program_graph,
+        three_address_code: three_address_code,
+        ir_warnings: ir_warnings,
+    })
+}
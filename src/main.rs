@@ -1,9 +1,4 @@
-mod utils;
-mod scanner;
-mod parser;
-mod semantics;
-mod irs;
-mod assembler;
+use rust_compiler::{utils, scanner, parser, semantics, assembler, evaluator, fuzzer, testing};
 
 fn get_writer(output: &Option<std::path::PathBuf>) -> Box<dyn std::io::Write> {
     match output {
@@ -14,7 +9,10 @@ fn get_writer(output: &Option<std::path::PathBuf>) -> Box<dyn std::io::Write> {
 
 fn main() {
     let args = utils::cli::parse();
-    let _input = std::fs::read_to_string(&args.input).expect("Filename is incorrect.");
+    let input = args.input.clone().unwrap_or_default();
+    if args.input.is_some() && !matches!(args.target, utils::cli::CompilerAction::Test) {
+        let _input = std::fs::read_to_string(&input).expect("Filename is incorrect.");
+    }
 
     if args.debug {
         eprintln!(
@@ -23,6 +21,8 @@ fn main() {
         );
     }
 
+    let lints = semantics::lints::LintConfig::from_flags(&args.warn, &args.allow).unwrap();
+
     // Use writeln!(writer, "template string") to write to stdout or file.
     let writer = get_writer(&args.output);
     match args.target {
@@ -30,16 +30,49 @@ fn main() {
             panic!("Invalid target");
         }
         utils::cli::CompilerAction::Scan => {
-            scanner::scanner::scan(&args.input, writer);
+            scanner::scanner::scan(&input, writer);
         }
         utils::cli::CompilerAction::Parse => {
-            parser::parser::parse(&args.input, writer, args.debug);
+            parser::parser::parse(&input, writer, args.debug);
         }
         utils::cli::CompilerAction::Inter => {
-            semantics::semantics::interpret(&args.input, writer, args.debug);
+            semantics::semantics::interpret(&input, writer, &lints, args.debug);
         }
         utils::cli::CompilerAction::Assembly => {
-            assembler::assembler::assemble(&args.input, writer, args.debug);
+            assembler::assembler::assemble(&input, writer, &lints, args.debug, args.verify_ir);
+        }
+        utils::cli::CompilerAction::Symbols => {
+            semantics::semantics::symbols(&input, writer, args.debug);
+        }
+        utils::cli::CompilerAction::Run => {
+            evaluator::evaluator::run(&input, writer, &lints, args.debug);
+        }
+        utils::cli::CompilerAction::Generate => {
+            fuzzer::generator::generate(args.seed, writer, args.debug);
+        }
+        utils::cli::CompilerAction::Reduce => {
+            fuzzer::reducer::reduce(&input, args.interesting.clone(), writer, args.debug);
+        }
+        utils::cli::CompilerAction::Snapshot => {
+            let snapshot_mode = if args.bless { testing::snapshot::SnapshotMode::Bless } else { testing::snapshot::SnapshotMode::Check };
+            testing::snapshot::snapshot(&input, writer, snapshot_mode);
+        }
+        utils::cli::CompilerAction::Test => {
+            let snapshot_mode = if args.bless {
+                testing::snapshot::SnapshotMode::Bless
+            } else if args.snapshot {
+                testing::snapshot::SnapshotMode::Check
+            } else {
+                testing::snapshot::SnapshotMode::Off
+            };
+            let options = testing::runner::RunnerOptions {
+                jobs: args.jobs,
+                timeout_secs: args.timeout,
+                snapshot_mode: snapshot_mode,
+                junit: args.junit.clone(),
+                json: args.json.clone(),
+            };
+            testing::runner::run(&input, writer, options, args.debug);
         }
     }
 }
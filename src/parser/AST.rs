@@ -1,4 +1,16 @@
 use super::visitor::Visitor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_NODE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/*
+Ids for expression and identifier nodes, unique across every AST built by the process. The node_id
+fields key the semantic side tables: expression types and identifier resolutions.
+Clones keep the id of the node they copy, so statements copied into the CFG still match the side tables.
+*/
+pub fn new_node_id() -> usize {
+    return NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed);
+}
 
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -37,6 +49,44 @@ impl ASTNode {
     pub fn accept<V: Visitor>(&self, visitor: &mut V) {
         visitor.visit_ast_node(self);
     }
+
+    // node id of an expression, None for statements and declarations
+    pub fn node_id(&self) -> Option<usize> {
+        match self {
+            ASTNode::MethodCall(method_call) => Some(method_call.node_id),
+            ASTNode::LenCall(len_call) => Some(len_call.node_id),
+            ASTNode::IntCast(int_cast) => Some(int_cast.node_id),
+            ASTNode::LongCast(long_cast) => Some(long_cast.node_id),
+            ASTNode::UnaryExpression(unary_expression) => Some(unary_expression.node_id),
+            ASTNode::BinaryExpression(binary_expression) => Some(binary_expression.node_id),
+            ASTNode::IndexExpression(index_expression) => Some(index_expression.node_id),
+            ASTNode::Identifier(identifier) => Some(identifier.node_id),
+            ASTNode::IntConstant(int_constant) => Some(int_constant.node_id),
+            ASTNode::LongConstant(long_constant) => Some(long_constant.node_id),
+            ASTNode::StringConstant(string_constant) => Some(string_constant.node_id),
+            ASTNode::BoolConstant(bool_constant) => Some(bool_constant.node_id),
+            ASTNode::CharConstant(char_constant) => Some(char_constant.node_id),
+            _ => None,
+        }
+    }
+
+    // source line of a statement, None for expressions and declarations
+    pub fn line_num(&self) -> Option<usize> {
+        match self {
+            ASTNode::IfStatement(if_statement) => Some(if_statement.line_num),
+            ASTNode::ForStatement(for_statement) => Some(for_statement.line_num),
+            ASTNode::WhileStatement(while_statement) => Some(while_statement.line_num),
+            ASTNode::ReturnStatement(return_statement) => Some(return_statement.line_num),
+            ASTNode::StatementControl(statement_control) => Some(statement_control.line_num),
+            ASTNode::Assignment(assignment) => match assignment.assign_var.as_ref() {
+                ASTNode::Identifier(identifier) => Some(identifier.line_num),
+                ASTNode::IndexExpression(index_expression) => Some(index_expression.id.line_num),
+                _ => None,
+            },
+            ASTNode::MethodCall(method_call) => Some(method_call.name.line_num),
+            _ => None,
+        }
+    }
 }
 
 // Top level declarations
@@ -152,6 +202,7 @@ pub struct IfStatement {
     pub condition: Box<ASTNode>, // any expression type specified by the grammar
     pub then_block: Box<Block>,
     pub else_block: Box<Option<Block>>,
+    pub line_num: usize, // source line of the if keyword
 }
 
 impl IfStatement {
@@ -168,6 +219,7 @@ pub struct ForStatement {
     pub end_expr: Box<ASTNode>,
     pub update_expr: Box<ASTNode>, // either ForUpdate or MethodCall
     pub block: Box<Block>,
+    pub line_num: usize, // source line of the for keyword
 }
 
 impl ForStatement {
@@ -182,6 +234,7 @@ impl ForStatement {
 pub struct WhileStatement {
     pub condition: Box<ASTNode>, // any expression type specified by the grammar
     pub block: Box<Block>,
+    pub line_num: usize, // source line of the while keyword
 }
 
 impl WhileStatement {
@@ -196,6 +249,7 @@ impl WhileStatement {
 pub struct ReturnStatement {
     pub func_type: String, // either void or type name
     pub expr: Box<Option<ASTNode>>, // any expression type specified by the grammar
+    pub line_num: usize, // source line of the return keyword
 }
 
 impl ReturnStatement {
@@ -209,6 +263,7 @@ impl ReturnStatement {
 #[derive(Debug)]
 pub struct StatementControl {
     pub op: String, // either Break or Continue
+    pub line_num: usize, // source line of the break or continue keyword
 }
 
 impl StatementControl {
@@ -240,6 +295,7 @@ impl Assignment {
 pub struct MethodCall {
     pub name: Box<Identifier>,
     pub args: Vec<Box<ASTNode>>,
+    pub node_id: usize,
 }
 
 impl MethodCall {
@@ -253,6 +309,7 @@ impl MethodCall {
 #[derive(Debug)]
 pub struct LenCall {
     pub id: Box<Identifier>,
+    pub node_id: usize,
 }
 
 impl LenCall {
@@ -266,6 +323,7 @@ impl LenCall {
 #[derive(Debug)]
 pub struct IntCast {
     pub cast_expr: Box<ASTNode>,
+    pub node_id: usize,
 }
 
 impl IntCast {
@@ -279,6 +337,7 @@ impl IntCast {
 #[derive(Debug)]
 pub struct LongCast {
     pub cast_expr: Box<ASTNode>,
+    pub node_id: usize,
 }
 
 impl LongCast {
@@ -293,6 +352,7 @@ impl LongCast {
 pub struct UnaryExpression {
     pub op: String,
     pub expr: Box<ASTNode>,
+    pub node_id: usize,
 }
 
 impl UnaryExpression {
@@ -307,7 +367,8 @@ impl UnaryExpression {
 pub struct BinaryExpression {
     pub op: String,
     pub left_expr: Box<ASTNode>,
-    pub right_expr: Box<ASTNode>
+    pub right_expr: Box<ASTNode>,
+    pub node_id: usize,
 }
 
 impl BinaryExpression {
@@ -325,6 +386,7 @@ For location case <id> '[' <expr> ']'
 pub struct IndexExpression {
     pub id: Box<Identifier>, 
     pub idx_expr: Box<ASTNode>,
+    pub node_id: usize,
 }
 
 impl IndexExpression {
@@ -353,6 +415,8 @@ impl ArrayLiteral {
 pub struct Identifier {
     pub name: String, 
     pub status: i32, // 0 for declare, 1 for read, 2 for write
+    pub line_num: usize, // source line the identifier appears on
+    pub node_id: usize,
 }
 
 impl Identifier {
@@ -370,6 +434,7 @@ Stores both decimal and hex numbers
 pub struct IntConstant {
     pub is_neg: bool,
     pub value: String,
+    pub node_id: usize,
 }
 
 impl IntConstant {
@@ -384,6 +449,7 @@ impl IntConstant {
 pub struct LongConstant {
     pub is_neg: bool,
     pub value: String,
+    pub node_id: usize,
 }
 
 impl LongConstant {
@@ -397,6 +463,7 @@ impl LongConstant {
 #[derive(Debug)]
 pub struct StringConstant {
     pub value: String,
+    pub node_id: usize,
 }
 
 impl StringConstant {
@@ -410,6 +477,7 @@ impl StringConstant {
 #[derive(Debug)]
 pub struct BoolConstant {
     pub value: bool,
+    pub node_id: usize,
 }
 
 impl BoolConstant {
@@ -423,6 +491,7 @@ impl BoolConstant {
 #[derive(Debug)]
 pub struct CharConstant {
     pub value: String,
+    pub node_id: usize,
 }
 
 impl CharConstant {
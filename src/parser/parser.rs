@@ -1,8 +1,13 @@
 use super::AST::{self};
 use std::path::Path;
-use super::super::scanner::scanner::scan_file;
+use super::super::scanner::scanner::{scan_file, scan_source};
 use std::collections::HashMap;
 use super::parser_printer::ParserPrinter;
+use super::super::utils::suggest::did_you_mean;
+
+// keywords that can start a statement or local declaration, and a top level declaration
+const STATEMENT_KEYWORDS: [&str; 10] = ["if", "for", "while", "return", "break", "continue", "int", "long", "bool", "const"];
+const DECL_KEYWORDS: [&str; 6] = ["import", "int", "long", "bool", "void", "const"];
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -36,6 +41,10 @@ impl ParserState {
         return self.tokens[self.token_idx].clone();
     }
 
+    fn cur_line(&self) -> usize {
+        return self.tokens[self.token_idx].line_num.parse().unwrap_or(0);
+    }
+
     fn consume(&mut self) {
         self.token_idx += 1;
     }
@@ -126,6 +135,7 @@ fn parse_int_literal(parser_state: &mut ParserState, is_neg: bool) -> Result<AST
             let int_val = AST::IntConstant {
                 is_neg: is_neg,
                 value: parser_state.cur_token().token_value.clone(),
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(int_val);
@@ -145,6 +155,7 @@ fn parse_long_literal(parser_state: &mut ParserState, is_neg: bool) -> Result<AS
             let long_val = AST::LongConstant {
                 is_neg: is_neg,
                 value: long_val,
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(long_val);
@@ -160,6 +171,7 @@ fn parse_char_literal(parser_state: &mut ParserState) -> Result<AST::CharConstan
             let extract_char = parser_state.cur_token().token_value.clone();
             let char_val = AST::CharConstant {
                 value: extract_char[1..extract_char.len()-1].to_string(),
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(char_val);
@@ -174,6 +186,7 @@ fn parse_bool_literal(parser_state: &mut ParserState) -> Result<AST::BoolConstan
         TokenType::Bool => {
             let bool_val = AST::BoolConstant {
                 value: parser_state.cur_token().token_value == "true",
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(bool_val);
@@ -188,6 +201,7 @@ fn parse_string_literal(parser_state: &mut ParserState) -> Result<AST::StringCon
         TokenType::String => {
             let string_val = AST::StringConstant {
                 value: parser_state.cur_token().token_value.clone(),
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(string_val);
@@ -228,12 +242,29 @@ fn parse_literal(parser_state: &mut ParserState) -> Result<AST::ASTNode, String>
     }  
 }
 
+fn parse_array_literal(parser_state: &mut ParserState) -> Result<AST::ArrayLiteral, String> {
+    parser_state.check_token("{", true)?;
+    let mut array_values: Vec<Box<AST::ASTNode>> = vec![];
+    loop {
+        array_values.push(Box::new(parse_literal(parser_state)?));
+        if parser_state.check_token(",", true) != Ok(()) {
+            break;
+        }
+    }
+    parser_state.check_token("}", true)?;
+    return Ok(AST::ArrayLiteral {
+        array_values: array_values,
+    });
+}
+
 fn parse_identifier(parser_state: &mut ParserState, status: i32) -> Result<AST::Identifier, String> {
     match parser_state.cur_token().token_type {
         TokenType::Identifier => {
             let id = AST::Identifier {
                 name: parser_state.cur_token().token_value.clone(),
                 status: status,
+                line_num: parser_state.cur_line(),
+                node_id: AST::new_node_id(),
             };
             parser_state.consume();
             return Ok(id);
@@ -251,6 +282,7 @@ fn parse_location(parser_state: &mut ParserState, status: i32) -> Result<AST::AS
         return Ok(AST::ASTNode::IndexExpression(AST::IndexExpression {
             id: Box::new(id),
             idx_expr: Box::new(idx_expr),
+            node_id: AST::new_node_id(),
         }));
     } else {
         return Ok(AST::ASTNode::Identifier(id));
@@ -278,6 +310,7 @@ fn parse_method_call(parser_state: &mut ParserState) -> Result<AST::MethodCall,
     return Ok(AST::MethodCall {
         name: Box::new(method_name),
         args: args,
+        node_id: AST::new_node_id(),
     });
 }
 
@@ -291,6 +324,7 @@ fn parse_stand_alone_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode
             parser_state.check_token(")", true)?;
             return Ok(AST::ASTNode::LenCall(AST::LenCall {
                 id: Box::new(id),
+                node_id: AST::new_node_id(),
             }))
         },
        "(" => {
@@ -310,10 +344,11 @@ fn parse_stand_alone_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode
                     return Ok(AST::ASTNode::LongConstant(parse_long_literal(parser_state, true)?));
                 },
                 _ => {
-                    let expr = parse_stand_alone_expr(parser_state)?;
+                    let expr = parse_cast_expr(parser_state)?;
                     return Ok(AST::ASTNode::UnaryExpression(AST::UnaryExpression {
                         op: "-".to_string(),
                         expr: Box::new(expr),
+                        node_id: AST::new_node_id(),
                     }))
  
                 }
@@ -321,10 +356,11 @@ fn parse_stand_alone_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode
         },
         "!" => {
             parser_state.consume();
-            let expr = parse_stand_alone_expr(parser_state)?;
+            let expr = parse_cast_expr(parser_state)?;
             return Ok(AST::ASTNode::UnaryExpression(AST::UnaryExpression {
                 op: "!".to_string(),
                 expr: Box::new(expr),
+                node_id: AST::new_node_id(),
             }))
         },
         _ => {
@@ -360,6 +396,7 @@ fn parse_cast_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode, Strin
             parser_state.check_token(")", true)?;
             return Ok(AST::ASTNode::IntCast(AST::IntCast {
                 cast_expr: Box::new(cast_expr),
+                node_id: AST::new_node_id(),
             }))
         },
         "long" => {
@@ -369,6 +406,7 @@ fn parse_cast_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode, Strin
             parser_state.check_token(")", true)?;
             return Ok(AST::ASTNode::LongCast(AST::LongCast {
                 cast_expr: Box::new(cast_expr),
+                node_id: AST::new_node_id(),
             }))
         },
  
@@ -388,6 +426,7 @@ fn parse_mul_op_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode, Str
             op,
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -403,6 +442,7 @@ fn parse_add_op_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode, Str
             op,
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -418,6 +458,7 @@ fn parse_comparison_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode,
             op,
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -433,6 +474,7 @@ fn parse_equality_expr(parser_state: &mut ParserState) -> Result<AST::ASTNode, S
             op,
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -447,6 +489,7 @@ fn parse_and_operator(parser_state: &mut ParserState) -> Result<AST::ASTNode, St
             op: "&&".to_string(),
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -461,6 +504,7 @@ fn parse_or_operator(parser_state: &mut ParserState) -> Result<AST::ASTNode, Str
             op: "||".to_string(),
             left_expr: Box::new(left),
             right_expr: Box::new(right),
+            node_id: AST::new_node_id(),
         });
     }
     return Ok(left);
@@ -473,12 +517,13 @@ fn parse_expression(parser_state: &mut ParserState) -> Result<AST::ASTNode, Stri
 
 fn parse_assign_expression(parser_state: &mut ParserState) -> Result<AST::Assignment, String> {
     let op = parser_state.cur_token().token_value;
+    let line_num = parser_state.cur_line();
     let default_var_name = "".to_string();
     match op.as_str() {
         "++" | "--" => {
             parser_state.consume();
             return Ok(AST::Assignment {
-                assign_var: Box::new(AST::ASTNode::Identifier(AST::Identifier { name: default_var_name, status: 2 })),
+                assign_var: Box::new(AST::ASTNode::Identifier(AST::Identifier { name: default_var_name, status: 2, line_num: line_num, node_id: AST::new_node_id() })),
                 assign_op: op,
                 expr: Box::new(None),
             });
@@ -487,7 +532,7 @@ fn parse_assign_expression(parser_state: &mut ParserState) -> Result<AST::Assign
             parser_state.consume();
             let assign_expr = parse_expression(parser_state)?;
             return Ok(AST::Assignment {
-                assign_var: Box::new(AST::ASTNode::Identifier(AST::Identifier { name: default_var_name, status: 2 })),
+                assign_var: Box::new(AST::ASTNode::Identifier(AST::Identifier { name: default_var_name, status: 2, line_num: line_num, node_id: AST::new_node_id() })),
                 assign_op: op,
                 expr: Box::new(Some(assign_expr)),
             });
@@ -499,6 +544,7 @@ fn parse_assign_expression(parser_state: &mut ParserState) -> Result<AST::Assign
 }
 
 fn parse_if_statement(parser_state: &mut ParserState, func_type: &str) -> Result<AST::IfStatement, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("if", true)?;
     parser_state.check_token("(", true)?;
     let condition_expr = parse_expression(parser_state)?;
@@ -512,10 +558,12 @@ fn parse_if_statement(parser_state: &mut ParserState, func_type: &str) -> Result
         condition: Box::new(condition_expr),
         then_block: Box::new(then_block),
         else_block: Box::new(else_block),
+        line_num: line_num,
     });
 }
 
 fn parse_for_statement(parser_state: &mut ParserState, func_type: &str) -> Result<AST::ForStatement, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("for", true)?;
     parser_state.check_token("(", true)?;
     let increment_var = parse_identifier(parser_state, 2)?;
@@ -556,10 +604,12 @@ fn parse_for_statement(parser_state: &mut ParserState, func_type: &str) -> Resul
         end_expr: Box::new(end_expr),
         update_expr: Box::new(update_expr),
         block: Box::new(block),
+        line_num: line_num,
     });
 }
 
 fn parse_while_statement(parser_state: &mut ParserState, func_type: &str) -> Result<AST::WhileStatement, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("while", true)?;
     parser_state.check_token("(", true)?;
     let condition_expr = parse_expression(parser_state)?;
@@ -568,14 +618,17 @@ fn parse_while_statement(parser_state: &mut ParserState, func_type: &str) -> Res
     return Ok(AST::WhileStatement {
         condition: Box::new(condition_expr),
         block: Box::new(block),
+        line_num: line_num,
     });
 }
 
 fn parse_return_statement(parser_state: &mut ParserState, func_type: &str) -> Result<AST::ReturnStatement, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("return", true)?;
     let mut return_statement_res= AST::ReturnStatement {
         func_type: func_type.to_string(),
-        expr: Box::new(None)
+        expr: Box::new(None),
+        line_num: line_num,
     };
     if parser_state.cur_token().token_value != ";" {
         return_statement_res.expr = Box::new(Some(parse_expression(parser_state)?));
@@ -585,18 +638,22 @@ fn parse_return_statement(parser_state: &mut ParserState, func_type: &str) -> Re
 }
 
 fn parse_break_statement(parser_state: &mut ParserState) -> Result<AST::StatementControl, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("break", true)?;
     parser_state.check_token(";", true)?;
     return Ok(AST::StatementControl {
         op: "break".to_string(),
+        line_num: line_num,
     });
 }
 
 fn parse_continue_statement(parser_state: &mut ParserState) -> Result<AST::StatementControl, String> {
+    let line_num = parser_state.cur_line();
     parser_state.check_token("continue", true)?;
     parser_state.check_token(";", true)?;
     return Ok(AST::StatementControl {
         op: "continue".to_string(),
+        line_num: line_num,
     });
 }
 
@@ -621,22 +678,37 @@ fn parse_statement(parser_state: &mut ParserState, func_type: &str) -> Result<AS
             return Ok(AST::ASTNode::StatementControl(parse_continue_statement(parser_state)?));
         },
         _ => {
-            let saved_token_idx = parser_state.token_idx;
-            match parse_method_call(parser_state) {
-                Ok(method_call) => {
-                    let method_call_res = Ok(AST::ASTNode::MethodCall(method_call));
-                    parser_state.check_token(";", true)?;
-                    return method_call_res;
-                },
-                Err(_) => {
-                    parser_state.token_idx = saved_token_idx;
-                    let assign_var = parse_location(parser_state, 2)?;
-                    let mut assign_expr = parse_assign_expression(parser_state)?;
-                    assign_expr.assign_var = Box::new(assign_var);
-                    parser_state.check_token(";", true)?;
-                    return Ok(AST::ASTNode::Assignment(assign_expr));
+            let first_token = parser_state.cur_token();
+            // an identifier followed by an assignment is a real location, anything else may be a misspelled keyword
+            let is_assignment = ["=", "+=", "-=", "*=", "/=", "%=", "++", "--", "["].iter().any(|op| parser_state.check_incr_token(op, 1));
+            return parse_call_or_assignment(parser_state).map_err(|e| {
+                if first_token.token_type != TokenType::Identifier || is_assignment {
+                    return e;
                 }
-            }
+                match did_you_mean(&first_token.token_value, &STATEMENT_KEYWORDS) {
+                    Some(suggestion) => format!("{} - {}", e, suggestion),
+                    None => e,
+                }
+            });
+        }
+    }
+}
+
+fn parse_call_or_assignment(parser_state: &mut ParserState) -> Result<AST::ASTNode, String> {
+    let saved_token_idx = parser_state.token_idx;
+    match parse_method_call(parser_state) {
+        Ok(method_call) => {
+            let method_call_res = Ok(AST::ASTNode::MethodCall(method_call));
+            parser_state.check_token(";", true)?;
+            return method_call_res;
+        },
+        Err(_) => {
+            parser_state.token_idx = saved_token_idx;
+            let assign_var = parse_location(parser_state, 2)?;
+            let mut assign_expr = parse_assign_expression(parser_state)?;
+            assign_expr.assign_var = Box::new(assign_var);
+            parser_state.check_token(";", true)?;
+            return Ok(AST::ASTNode::Assignment(assign_expr));
         }
     }
 }
@@ -690,7 +762,7 @@ fn parse_field_decl(parser_state: &mut ParserState) -> Result<AST::FieldDecl, St
         let var_id = parse_identifier(parser_state, 0)?;
         let mut is_array = false;
         let mut array_len: Option<AST::IntConstant> = None;
-        let initializer: Option<AST::ASTNode> = None;
+        let mut initializer: Option<AST::ASTNode> = None;
 
         // case if we have id[int] initializer
         if parser_state.cur_token().token_value.clone() == "[" {
@@ -701,6 +773,15 @@ fn parse_field_decl(parser_state: &mut ParserState) -> Result<AST::FieldDecl, St
             parser_state.check_token("]", true)?;
             is_array = true;
         }
+
+        // case if we have id = literal or id[] = {literal, ...} initializer
+        if parser_state.check_token("=", true) == Ok(()) {
+            if parser_state.cur_token().token_value == "{" {
+                initializer = Some(AST::ASTNode::ArrayLiteral(parse_array_literal(parser_state)?));
+            } else {
+                initializer = Some(parse_literal(parser_state)?);
+            }
+        }
         
         // add new var to array
         vars.push(Box::new(AST::VarDecl {
@@ -789,31 +870,48 @@ fn parse_program(parser_state: &mut ParserState) -> Result<AST::Program, String>
 
     // end check
     if parser_state.cur_token().token_value != "EOF" {
-        return Err(format!("Line: {} - Error - expected EOF, got: {:?}", parser_state.cur_token().line_num, parser_state.cur_token().token_value));
+        let mut suggestion = String::new();
+        if parser_state.cur_token().token_type == TokenType::Identifier {
+            if let Some(keyword) = did_you_mean(&parser_state.cur_token().token_value, &DECL_KEYWORDS) {
+                suggestion = format!(" - {}", keyword);
+            }
+        }
+        return Err(format!("Line: {} - Error - expected EOF, got: {:?}{}", parser_state.cur_token().line_num, parser_state.cur_token().token_value, suggestion));
     }
 
     return Ok(program);
 }
 
+// parse tokens already produced by the scanner
+pub fn parse_tokens(tokens: Vec<String>) -> Result<AST::Program, Vec<String>> {
+    let mut parser_state = ParserState {
+        tokens: tokens.iter().map(|x| unpack_token(x.as_str())).collect(),
+        token_idx: 0,
+    };
+    // Add EOF token
+    parser_state.tokens.push(Token {
+        token_type: TokenType::EOF,
+        token_value: "EOF".to_string(),
+        line_num: "".to_string(),
+    });
+    match parse_program(&mut parser_state) {
+        Ok(parsed_program) => return Ok(parsed_program),
+        Err(e) => return Err(vec![e])
+    }
+}
+
+// parse decaf source held in memory
+pub fn parse_source(source: &str) -> Result<AST::Program, Vec<String>> {
+    match scan_source(source) {
+        Ok(tokens) => return parse_tokens(tokens),
+        Err(e) => return Err(e)
+    }
+}
+
 pub fn parse_file(file_path: &Path) -> Result<AST::Program, Vec<String>> {
     // Lex file first
     match scan_file(file_path) {
-        Ok(tokens) => {    
-            let mut parser_state = ParserState {
-                tokens: tokens.iter().map(|x| unpack_token(x.as_str())).collect(),
-                token_idx: 0,
-            };
-            // Add EOF token
-            parser_state.tokens.push(Token {
-                token_type: TokenType::EOF,
-                token_value: "EOF".to_string(),
-                line_num: "".to_string(),
-            });
-            match parse_program(&mut parser_state) {
-                Ok(parsed_program) => return Ok(parsed_program),
-                Err(e) => return Err(vec![e])
-            }
-        }, 
+        Ok(tokens) => return parse_tokens(tokens),
         Err(e) => return Err(e)
     }
 }
@@ -825,6 +923,7 @@ pub fn parse(file_path: &Path, mut writer: Box<dyn std::io::Write>, debug: bool)
             if debug {
                 let mut pretty_printer = ParserPrinter::new();
                 parsed_program.accept(&mut pretty_printer);
+                print!("{}", pretty_printer.output);
             }
             std::process::exit(0);
         },
@@ -2,38 +2,59 @@ use super::AST::{self};
 use super::visitor::Visitor;
 
 pub struct ParserPrinter {
+    pub output: String,
     indent: usize,
     // flags for parser printer state
     new_line: bool,
     in_for_loop_def: bool,
-    in_expr: bool,
-    set_colon: bool,
 }
 
 impl ParserPrinter {
     pub fn new() -> ParserPrinter {
         ParserPrinter {
+            output: String::new(),
             indent: 0,
             new_line: true,
             in_for_loop_def: false,
-            in_expr: false,
-            set_colon: false,
         }
     }
 
     fn tab_print(&mut self, print_str: &str) {
         if self.new_line {
             for _ in 0..self.indent {
-                print!("  ");
+                self.output.push_str("  ");
             }
         }
-        print!("{}", print_str);
+        self.output.push_str(print_str);
         if print_str.ends_with('\n') {
             self.new_line = true;
         } else {
             self.new_line = false;
         }
     }
+
+    // print a sub expression, adding parentheses when the grammar would otherwise regroup it
+    fn print_operand(&mut self, expr: &AST::ASTNode, needs_parens: bool) {
+        if needs_parens {
+            self.tab_print("(");
+            expr.accept(self);
+            self.tab_print(")");
+        } else {
+            expr.accept(self);
+        }
+    }
+}
+
+// binding strength of binary operators, higher binds tighter
+fn precedence(op: &str) -> u32 {
+    match op {
+        "||" => 1,
+        "&&" => 2,
+        "==" | "!=" => 3,
+        "<" | "<=" | ">" | ">=" => 4,
+        "+" | "-" => 5,
+        _ => 6,
+    }
 }
 
 impl Visitor for ParserPrinter {
@@ -95,6 +116,9 @@ impl Visitor for ParserPrinter {
         }
         for statement in &block.statements {
             statement.accept(self);
+            if let AST::ASTNode::MethodCall(_) = statement.as_ref() {
+                self.tab_print(";\n");
+            }
         }
         self.indent -= 1;
         self.tab_print("}\n");
@@ -167,7 +191,6 @@ impl Visitor for ParserPrinter {
     }
 
     fn visit_assignment(&mut self, assignment: &AST::Assignment) {
-        self.set_colon = false;
         assignment.assign_var.accept(self);
         self.tab_print(" ");
         self.tab_print(&assignment.assign_op);
@@ -175,7 +198,7 @@ impl Visitor for ParserPrinter {
             self.tab_print(" ");
             expr.accept(self);
         }
-        if !self.in_for_loop_def && !self.set_colon {
+        if !self.in_for_loop_def {
             self.tab_print(";\n");
         }
     }
@@ -191,10 +214,6 @@ impl Visitor for ParserPrinter {
             }
         }
         self.tab_print(")");
-        if !self.in_expr {
-            self.tab_print(";\n");
-            self.set_colon = true;
-        }
     }
 
     fn visit_len_call(&mut self, len_call: &AST::LenCall) {
@@ -203,30 +222,53 @@ impl Visitor for ParserPrinter {
         self.tab_print(")");
     }
 
+    fn visit_int_cast(&mut self, int_cast: &AST::IntCast) {
+        self.tab_print("int(");
+        int_cast.cast_expr.accept(self);
+        self.tab_print(")");
+    }
+
+    fn visit_long_cast(&mut self, long_cast: &AST::LongCast) {
+        self.tab_print("long(");
+        long_cast.cast_expr.accept(self);
+        self.tab_print(")");
+    }
+
     fn visit_unary_expression(&mut self, unary_expression: &AST::UnaryExpression) {
-        self.in_expr = true;
         self.tab_print(&unary_expression.op);
-        unary_expression.expr.accept(self);
-        self.in_expr = false;
+        // parenthesize compound or negative operands so "- -x" is not scanned as "--"
+        let needs_parens = match unary_expression.expr.as_ref() {
+            AST::ASTNode::BinaryExpression(_) | AST::ASTNode::UnaryExpression(_) => true,
+            AST::ASTNode::IntConstant(int_constant) => int_constant.is_neg,
+            AST::ASTNode::LongConstant(long_constant) => long_constant.is_neg,
+            _ => false,
+        };
+        self.print_operand(&unary_expression.expr, needs_parens);
     }
 
     fn visit_binary_expression(&mut self, binary_expression: &AST::BinaryExpression) {
-        self.in_expr = true;
-        binary_expression.left_expr.accept(self);
+        let op_precedence = precedence(&binary_expression.op);
+        // operators are left associative, so an equal precedence right operand needs parentheses
+        let left_parens = match binary_expression.left_expr.as_ref() {
+            AST::ASTNode::BinaryExpression(left) => precedence(&left.op) < op_precedence,
+            _ => false,
+        };
+        let right_parens = match binary_expression.right_expr.as_ref() {
+            AST::ASTNode::BinaryExpression(right) => precedence(&right.op) <= op_precedence,
+            _ => false,
+        };
+        self.print_operand(&binary_expression.left_expr, left_parens);
         self.tab_print(" ");
         self.tab_print(&binary_expression.op);
         self.tab_print(" ");
-        binary_expression.right_expr.accept(self);
-        self.in_expr = false;
+        self.print_operand(&binary_expression.right_expr, right_parens);
     }
 
     fn visit_index_expression(&mut self, index_expression: &AST::IndexExpression) {
-        self.in_expr = true;
         index_expression.id.accept(self);
         self.tab_print("[");
         index_expression.idx_expr.accept(self);
         self.tab_print("]");
-        self.in_expr = false;
     }
 
     fn visit_array_literal(&mut self, array_literal: &AST::ArrayLiteral) {
@@ -251,6 +293,14 @@ impl Visitor for ParserPrinter {
         self.tab_print(&int_constant.value);
     }
 
+    fn visit_long_constant(&mut self, long_constant: &AST::LongConstant) {
+        if long_constant.is_neg {
+            self.tab_print("-");
+        }
+        self.tab_print(&long_constant.value);
+        self.tab_print("L");
+    }
+
     fn visit_string_constant(&mut self, string_constant: &AST::StringConstant) {
         self.tab_print(&string_constant.value);
     }
@@ -260,7 +310,9 @@ impl Visitor for ParserPrinter {
     }
 
     fn visit_char_constant(&mut self, char_constant: &AST::CharConstant) {
+        self.tab_print("'");
         self.tab_print(&char_constant.value);
+        self.tab_print("'");
     }
 }
 
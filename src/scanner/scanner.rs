@@ -382,6 +382,11 @@ fn scan_program(file_str: String) -> Result<Vec<String>, Vec<String>> {
     return Ok(tokens);
 }
 
+// scan decaf source held in memory
+pub fn scan_source(source: &str) -> Result<Vec<String>, Vec<String>> {
+    return scan_program(source.to_string());
+}
+
 pub fn scan_file(file_path: &Path) -> Result<Vec<String>, Vec<String>> {
     let mut file = File::open(file_path).expect("Failed to Open File");
     let mut file_str = String::new();
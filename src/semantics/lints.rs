@@ -0,0 +1,343 @@
+use super::semantics::AnalyzedProgram;
+use super::super::irs::cfg::cfg_creator::construct_program_graph;
+use super::super::irs::cfg::reachability::{constant_false_loop_lines, falls_off_end, unreachable_lines};
+
+use std::collections::{HashMap, HashSet};
+
+/*
+Warnings reported on a program that passed semantic checking. Every lint has a level: allowed lints
+are not reported, warned lints are printed without failing the compile and denied lints are errors.
+Every lint warns by default, the command line -W and -A flags change their levels. A missing return
+only fails the program when the method actually falls off its end at run time (exit -2), so it is
+not an error unless asked for with -W error=missing-return.
+*/
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(Debug)]
+pub enum Lint {
+    UnusedVariable, // locals
+    UnusedParameter,
+    UnusedGlobal,
+    UnusedImport,
+    UnusedMethod, // every method but main
+    UnreachableCode, // statements after a return, break, continue or infinite loop
+    ConstantFalseLoop,
+    MissingReturn, // a non-void method can reach the end of its body
+}
+
+pub const LINTS: [Lint; 8] = [
+    Lint::UnusedVariable,
+    Lint::UnusedParameter,
+    Lint::UnusedGlobal,
+    Lint::UnusedImport,
+    Lint::UnusedMethod,
+    Lint::UnreachableCode,
+    Lint::ConstantFalseLoop,
+    Lint::MissingReturn,
+];
+
+impl Lint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedGlobal => "unused-global",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnusedMethod => "unused-method",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::ConstantFalseLoop => "constant-false-loop",
+            Lint::MissingReturn => "missing-return",
+        }
+    }
+
+    pub fn default_level(&self) -> LintLevel {
+        return LintLevel::Warn;
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        return LINTS.iter().find(|lint| lint.name() == name).copied();
+    }
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct LintConfig {
+    levels: HashMap<Lint, LintLevel>,
+}
+
+impl Default for LintConfig {
+    fn default() -> LintConfig {
+        LintConfig {
+            levels: LINTS.iter().map(|lint| (*lint, lint.default_level())).collect(),
+        }
+    }
+}
+
+impl LintConfig {
+    /*
+    Builds the levels from the -W and -A flags. Each flag names a lint or all, and -W error (or
+    error=<lint>) turns warnings into errors. Flags naming all are applied before flags naming a
+    single lint, so "-A all -W unused-import" only reports unused imports, and promotions come last.
+    */
+    pub fn from_flags(warn: &[String], allow: &[String]) -> Result<LintConfig, String> {
+        let mut config = LintConfig::default();
+        let mut promoted: Vec<Option<Lint>> = vec![];
+        let mut single: Vec<(Lint, LintLevel)> = vec![];
+        let flags = warn.iter().map(|flag| (flag, LintLevel::Warn)).chain(allow.iter().map(|flag| (flag, LintLevel::Allow)));
+        for (flag, level) in flags {
+            if flag == "all" {
+                config.set_all(level);
+                continue;
+            }
+            if level == LintLevel::Warn && flag == "error" {
+                promoted.push(None);
+                continue;
+            }
+            if let Some(name) = flag.strip_prefix("error=").filter(|_| level == LintLevel::Warn) {
+                match Lint::from_name(name) {
+                    Some(lint) => promoted.push(Some(lint)),
+                    None => return Err(unknown_lint(name)),
+                }
+                continue;
+            }
+            match Lint::from_name(flag) {
+                Some(lint) => single.push((lint, level)),
+                None => return Err(unknown_lint(flag)),
+            }
+        }
+        for (lint, level) in single {
+            config.levels.insert(lint, level);
+        }
+        for lint in promoted {
+            match lint {
+                Some(lint) => {
+                    config.levels.insert(lint, LintLevel::Deny);
+                },
+                None => {
+                    for level in config.levels.values_mut() {
+                        if *level == LintLevel::Warn {
+                            *level = LintLevel::Deny;
+                        }
+                    }
+                },
+            }
+        }
+        return Ok(config);
+    }
+
+    fn set_all(&mut self, level: LintLevel) {
+        for lint in LINTS {
+            self.levels.insert(lint, level);
+        }
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        return *self.levels.get(&lint).unwrap_or(&LintLevel::Warn);
+    }
+}
+
+fn unknown_lint(name: &str) -> String {
+    let names: Vec<&str> = LINTS.iter().map(|lint| lint.name()).collect();
+    return format!("unknown lint {}, expected all, error, error=<lint> or one of: {}", name, names.join(", "));
+}
+
+/*
+Diagnostics of every lint that is not allowed, split by level and sorted by line
+*/
+#[derive(Default)]
+pub struct LintReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    reported: Vec<(usize, LintLevel, String)>, // line, level and message of each diagnostic before sorting
+}
+
+impl LintReport {
+    pub fn report(&mut self, config: &LintConfig, lint: Lint, line_num: usize, message: &str) {
+        let level = config.level(lint);
+        let diagnostic = match level {
+            LintLevel::Allow => return,
+            LintLevel::Warn => format!("Line: {} - Warning - {} [{}]", line_num, message, lint.name()),
+            LintLevel::Deny => format!("Line: {} - Error - {} [{}]", line_num, message, lint.name()),
+        };
+        self.reported.push((line_num, level, diagnostic));
+    }
+
+    fn finish(&mut self) {
+        self.reported.sort_by_key(|(line_num, _, _)| *line_num);
+        for (_, level, diagnostic) in self.reported.drain(..) {
+            if level == LintLevel::Deny {
+                self.errors.push(diagnostic);
+            } else {
+                self.warnings.push(diagnostic);
+            }
+        }
+    }
+}
+
+// declarations whose entry is never read: assignments and increments alone do not use a variable
+fn check_unused(analyzed_program: &AnalyzedProgram, config: &LintConfig, report: &mut LintReport) {
+    let mut params: HashSet<usize> = HashSet::new();
+    for method in &analyzed_program.ast.methods {
+        for arg in &method.args {
+            params.insert(arg.name.node_id);
+        }
+    }
+    let read: HashSet<usize> = analyzed_program.occurrences.iter()
+        .filter(|occurrence| occurrence.status == 1)
+        .map(|occurrence| occurrence.decl_id)
+        .collect();
+
+    for declaration in analyzed_program.occurrences.iter().filter(|occurrence| occurrence.status == 0) {
+        let entry = match analyzed_program.resolutions.get(&declaration.node_id) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        if read.contains(&declaration.decl_id) {
+            continue;
+        }
+        let name = declaration.name.as_str();
+        match entry.get_kind() {
+            "import" => report.report(config, Lint::UnusedImport, declaration.line_num, &format!("import {} is never called", name)),
+            "method" if name != "main" => report.report(config, Lint::UnusedMethod, declaration.line_num, &format!("method {} is never called", name)),
+            "method" => (),
+            _ if declaration.scope == 0 => report.report(config, Lint::UnusedGlobal, declaration.line_num, &format!("global variable {} is never read", name)),
+            _ if params.contains(&declaration.node_id) => report.report(config, Lint::UnusedParameter, declaration.line_num, &format!("parameter {} is never read", name)),
+            _ => report.report(config, Lint::UnusedVariable, declaration.line_num, &format!("local variable {} is never read", name)),
+        }
+    }
+}
+
+// control flow of every method: dead statements, loops that never run and non-void methods without a final return
+fn check_flow(analyzed_program: &AnalyzedProgram, config: &LintConfig, report: &mut LintReport) {
+    let program_graph = construct_program_graph(analyzed_program);
+    for method_decl in &analyzed_program.ast.methods {
+        let cfg = match program_graph.method_graphs.get(&method_decl.name.name) {
+            Some(cfg) => cfg,
+            None => continue,
+        };
+        for line_num in unreachable_lines(cfg) {
+            report.report(config, Lint::UnreachableCode, line_num, "statement is unreachable");
+        }
+        for line_num in constant_false_loop_lines(cfg) {
+            report.report(config, Lint::ConstantFalseLoop, line_num, "loop condition is always false, the body never runs");
+        }
+        if method_decl.type_name != "void" && falls_off_end(cfg) {
+            report.report(config, Lint::MissingReturn, method_decl.name.line_num,
+                &format!("method {} can reach the end of its body without returning a value", method_decl.name.name));
+        }
+    }
+}
+
+pub fn lint_program(analyzed_program: &AnalyzedProgram, config: &LintConfig) -> LintReport {
+    let mut report = LintReport::default();
+    check_unused(analyzed_program, config, &mut report);
+    check_flow(analyzed_program, config, &mut report);
+    report.finish();
+    return report;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_program, Lint, LintConfig, LintLevel, LINTS};
+    use crate::parser::parser::parse_source;
+    use crate::semantics::semantics::{analyze_program, check_program};
+
+    fn from_flags(warn: &[&str], allow: &[&str]) -> LintConfig {
+        let warn: Vec<String> = warn.iter().map(|flag| flag.to_string()).collect();
+        let allow: Vec<String> = allow.iter().map(|flag| flag.to_string()).collect();
+        LintConfig::from_flags(&warn, &allow).expect("flags should be valid")
+    }
+
+    #[test]
+    fn every_lint_warns_by_default() {
+        let config = LintConfig::default();
+        for lint in LINTS {
+            assert_eq!(config.level(lint), lint.default_level());
+            assert_eq!(config.level(lint), LintLevel::Warn);
+        }
+    }
+
+    #[test]
+    fn single_lints_apply_after_all() {
+        let config = from_flags(&["unused-import"], &["all"]);
+        assert_eq!(config.level(Lint::UnusedImport), LintLevel::Warn);
+        assert_eq!(config.level(Lint::UnusedVariable), LintLevel::Allow);
+        assert_eq!(config.level(Lint::MissingReturn), LintLevel::Allow);
+
+        let config = from_flags(&["all"], &["unused-import"]);
+        assert_eq!(config.level(Lint::UnusedImport), LintLevel::Allow);
+        assert_eq!(config.level(Lint::UnusedVariable), LintLevel::Warn);
+    }
+
+    #[test]
+    fn warn_all_does_not_lower_promotions() {
+        let config = from_flags(&["error=missing-return", "all"], &[]);
+        assert_eq!(config.level(Lint::MissingReturn), LintLevel::Deny);
+        assert_eq!(config.level(Lint::UnusedVariable), LintLevel::Warn);
+        let config = from_flags(&["error", "all"], &["unused-import"]);
+        assert_eq!(config.level(Lint::MissingReturn), LintLevel::Deny);
+        assert_eq!(config.level(Lint::UnusedImport), LintLevel::Allow);
+    }
+
+    #[test]
+    fn error_promotes_warnings() {
+        let config = from_flags(&["error=unused-method"], &[]);
+        assert_eq!(config.level(Lint::UnusedMethod), LintLevel::Deny);
+        assert_eq!(config.level(Lint::UnusedVariable), LintLevel::Warn);
+
+        // promotions come last, and leave allowed lints alone
+        let config = from_flags(&["error"], &["unused-global"]);
+        assert_eq!(config.level(Lint::UnusedVariable), LintLevel::Deny);
+        assert_eq!(config.level(Lint::UnusedGlobal), LintLevel::Allow);
+        let config = from_flags(&["error=unused-global"], &["unused-global"]);
+        assert_eq!(config.level(Lint::UnusedGlobal), LintLevel::Deny);
+    }
+
+    #[test]
+    fn missing_return_is_a_warning_unless_promoted() {
+        let src = "int f(int x) {\n if (x > 0) {\n return x;\n }\n}\nvoid main() { f(1); }";
+        let ast = parse_source(src).expect("test program should parse");
+        let (_, warnings) = check_program(ast, &LintConfig::default(), false).expect("a missing return is only a warning");
+        assert_eq!(warnings, vec!["Line: 1 - Warning - method f can reach the end of its body without returning a value [missing-return]"]);
+        let ast = parse_source(src).expect("test program should parse");
+        assert!(check_program(ast, &from_flags(&["error=missing-return"], &[]), false).is_err());
+    }
+
+    #[test]
+    fn unknown_lints_are_rejected() {
+        assert!(LintConfig::from_flags(&["bogus".to_string()], &[]).is_err());
+        assert!(LintConfig::from_flags(&["error=bogus".to_string()], &[]).is_err());
+        assert!(LintConfig::from_flags(&[], &["error".to_string()]).is_err());
+    }
+
+    #[test]
+    fn reports_unused_declarations_by_kind() {
+        let ast = parse_source(r#"
+            import printf;
+            import unused_import;
+            int g, unused_global;
+            int helper(int used, int unused_param) { int unused_local; unused_local = used; return used; }
+            void unused_method() { }
+            void main() { printf("%d", helper(g, 2)); }
+        "#).expect("test program should parse");
+        let analyzed_program = analyze_program(ast, false).expect("test program should pass semantic checks");
+        let report = lint_program(&analyzed_program, &LintConfig::default());
+        assert!(report.errors.is_empty());
+        let lints: Vec<&str> = report.warnings.iter().map(|warning| &warning[warning.rfind('[').unwrap()..]).collect();
+        assert_eq!(lints, vec!["[unused-import]", "[unused-global]", "[unused-parameter]", "[unused-variable]", "[unused-method]"]);
+    }
+}
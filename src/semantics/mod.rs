@@ -1,2 +1,3 @@
 pub mod semantics;
-pub mod symbol_table;
\ No newline at end of file
+pub mod symbol_table;
+pub mod lints;
@@ -1,6 +1,7 @@
 use crate::parser::AST::ASTNode;
 
-use super::super::parser::parser::parse_file;
+use super::super::parser::parser::{parse_file, parse_source};
+use super::super::parser::parser_printer::ParserPrinter;
 use super::super::parser::AST;
 use super::super::parser::visitor::Visitor;
 use super::symbol_table::{Entry,
@@ -10,10 +11,14 @@ use super::symbol_table::{Entry,
                           ImportEntry,
                           Table,
                           Type,
+                          Occurrence,
                         };
 
+use super::super::utils::suggest::did_you_mean;
+use super::lints::{lint_program, LintConfig};
+
 use core::panic;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct Interpreter {
     scopes: Vec<Box<Table>>, // vec of scopes which scope graph will reference by index
@@ -32,6 +37,10 @@ pub struct Interpreter {
 
     // func result holders
     result_expr_type: Type,
+    expr_types: HashMap<usize, Type>, // node id of each checked expression -> its type
+    resolutions: HashMap<usize, Entry>, // node id of each identifier use -> the entry it refers to
+    occurrences: Vec<Occurrence>, // every resolved identifier in visiting order
+    undeclared: HashSet<String>, // undeclared names already reported in the current method
 
     // debug mode
     debug: bool,
@@ -39,7 +48,7 @@ pub struct Interpreter {
 
 impl Interpreter {
     // find whether variable has been declared
-    fn find_var(&mut self, var_name: &str) -> Result<Entry, ()> {
+    fn find_var(&mut self, var_name: &str, callable: bool) -> Result<Entry, ()> {
         // Rule 2: No identifier is used before it is declared
         // Rule 12: An ⟨id⟩ used as a ⟨location⟩ must name a declared local/global variable or parameter.
 
@@ -56,10 +65,75 @@ impl Interpreter {
             };
         }
 
-        self.push_error(&format!("Error: Identifier {} is used before it is declared.", var_name));
+        // later uses of the same undeclared name are follow-on errors
+        if self.undeclared.insert(var_name.to_string()) {
+            let visible_names = self.visible_names(callable);
+            let candidates: Vec<&str> = visible_names.iter().map(|name| name.as_str()).collect();
+            let mut message = format!("Error: Identifier {} is used before it is declared.", var_name);
+            if let Some(suggestion) = did_you_mean(var_name, &candidates) {
+                message = format!("{} Hint: {}", message, suggestion);
+            }
+            self.push_error(&message);
+        }
         return Err(());
     }
 
+    // names of the methods and imports (callable) or variables visible from the current scope
+    fn visible_names(&self, callable: bool) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        let mut search_scope_ind = Some(self.cur_scope_ind);
+        while let Some(scope_ind) = search_scope_ind {
+            let cur_scope = self.scopes[scope_ind].as_ref();
+            for (name, entry) in cur_scope.entries.iter() {
+                if ["method", "import"].contains(&entry.get_kind()) == callable {
+                    names.push(name.clone());
+                }
+            }
+            search_scope_ind = cur_scope.parent_ind;
+        }
+        return names;
+    }
+
+    // find_var for an identifier node, remembering the entry it resolved to
+    fn resolve(&mut self, identifier: &AST::Identifier) -> Result<Entry, ()> {
+        let result = self.find_var(identifier.name.as_str(), false);
+        if let Ok(entry) = &result {
+            self.record_resolution(identifier, entry.clone());
+        }
+        return result;
+    }
+
+    // resolve for the name of a method call, suggesting methods and imports rather than variables
+    fn resolve_callable(&mut self, identifier: &AST::Identifier) -> Result<Entry, ()> {
+        let result = self.find_var(identifier.name.as_str(), true);
+        if let Ok(entry) = &result {
+            self.record_resolution(identifier, entry.clone());
+        }
+        return result;
+    }
+
+    // write_to_table for a declaring identifier, which resolves to its own entry
+    fn declare(&mut self, identifier: &AST::Identifier, entry: Entry) {
+        self.write_to_table(identifier.name.as_str(), entry.clone());
+        self.record_resolution(identifier, entry);
+    }
+
+    fn record_resolution(&mut self, identifier: &AST::Identifier, entry: Entry) {
+        // index expressions resolve their identifier twice, only record it once
+        if self.resolutions.contains_key(&identifier.node_id) {
+            return;
+        }
+        self.occurrences.push(Occurrence {
+            node_id: identifier.node_id,
+            name: identifier.name.clone(),
+            line_num: identifier.line_num,
+            status: identifier.status,
+            decl_id: entry.get_id(),
+            scope: entry.get_scope(),
+        });
+        self.resolutions.insert(identifier.node_id, entry);
+    }
+
     fn check_declared(&mut self, var_name: &str) -> bool {
         // Rule 1: No identifier is declared twice in the same scope
         let cur_scope = self.scopes[self.cur_scope_ind].as_ref();
@@ -100,6 +174,7 @@ impl Interpreter {
             name: arg_name.to_string(),
             var_type: arg_type.clone(),
             is_const: false,
+            const_value: None,
             scope: self.cur_scope_ind,
             id: self.incr_var_count(),
         };
@@ -170,7 +245,7 @@ impl Visitor for Interpreter {
             scope: self.cur_scope_ind,
             id: self.incr_var_count(),
         };
-        self.write_to_table(import_id.as_str(), Entry::Import(import_entry));
+        self.declare(&import_decl.import_id, Entry::Import(import_entry));
     }
 
     fn visit_field_decl(&mut self, field_decl: &AST::FieldDecl) {
@@ -181,6 +256,7 @@ impl Visitor for Interpreter {
 
     fn visit_method_decl(&mut self, method_decl: &AST::MethodDecl) {
         self.init_method = true;
+        self.undeclared.clear();
 
         // Rule 1: No identifier is declared twice in the same scope
         self.visit_identifier(&method_decl.name);
@@ -203,7 +279,7 @@ impl Visitor for Interpreter {
         for arg in &method_decl.args {
             method_entry.param_list.push(self.extract_method_arg_var(arg.as_ref()));
         }
-        self.write_to_table(method_decl.name.name.as_str(), Entry::Method(method_entry));
+        self.declare(&method_decl.name, Entry::Method(method_entry));
 
         // create new scope
         let method_table = Table {
@@ -306,7 +382,7 @@ impl Visitor for Interpreter {
             };
 
             let var_id = self.incr_var_count();
-            self.write_to_table(var_name, Entry::Array( ArrayEntry {
+            self.declare(&var_decl.name, Entry::Array( ArrayEntry {
                 name: var_name.to_string(),
                 var_type: array_type,
                 is_const: var_decl.is_const,
@@ -323,11 +399,18 @@ impl Visitor for Interpreter {
                                         .unwrap());
             }
 
+            // record the initializer of const variables so later stages can propagate it
+            let mut const_value: Option<AST::ASTNode> = None;
+            if var_decl.is_const {
+                const_value = var_decl.initializer.as_ref().clone();
+            }
+
             let var_id = self.incr_var_count();
-            self.write_to_table(var_name, Entry::Var( VarEntry {
+            self.declare(&var_decl.name, Entry::Var( VarEntry {
                 name: var_name.to_string(),
                 var_type: self.init_type.clone(),
                 is_const: var_decl.is_const,
+                const_value: const_value,
                 scope: self.cur_scope_ind,
                 id: var_id,
             }));
@@ -339,16 +422,13 @@ impl Visitor for Interpreter {
         self.visit_identifier(method_arg_decl.name.as_ref());
 
         let method_entry = self.extract_method_arg_var(method_arg_decl);
-        let arg_name = method_arg_decl.name.as_ref()
-                                                .name
-                                                .as_str();
-        self.write_to_table(arg_name, Entry::Var(method_entry));
+        self.declare(&method_arg_decl.name, Entry::Var(method_entry));
     }
 
     fn visit_if_statement(&mut self, if_statement: &AST::IfStatement) {
         // Rule 16: The ⟨expr⟩ in an if or while statement must have type bool , as well as the second ⟨expr⟩ of a for statement.   
         self.visit_expression(if_statement.condition.as_ref());
-        if self.result_expr_type != Type::Bool {
+        if self.result_expr_type != Type::Bool && !self.result_expr_type.is_poison() {
             self.push_error("Error: The expression in an if statement must have type bool.");
         }
         self.visit_block(if_statement.then_block.as_ref());
@@ -358,15 +438,12 @@ impl Visitor for Interpreter {
     }
 
     fn visit_for_statement(&mut self, for_statement: &AST::ForStatement) {
-        let incr_var = for_statement.start_assignment.assign_var.as_ref();
-        self.visit_location(incr_var);
-
         // Visit initial incr var assignment to check validity
         self.visit_assignment(&for_statement.start_assignment);
 
         // Rule 16: The ⟨expr⟩ in an if or while statement must have type bool , as well as the second ⟨expr⟩ of a for statement.
         self.visit_expression(&for_statement.end_expr);
-        if self.result_expr_type != Type::Bool {
+        if self.result_expr_type != Type::Bool && !self.result_expr_type.is_poison() {
             self.push_error("Error: The ending condition expression in a for statement must have type bool.");
         }
         // Visit update expression to check validity
@@ -388,7 +465,7 @@ impl Visitor for Interpreter {
     fn visit_while_statement(&mut self, while_statement: &AST::WhileStatement) {
         // Rule 16: The ⟨expr⟩ in an if or while statement must have type bool , as well as the second ⟨expr⟩ of a for statement.
         self.visit_expression(&while_statement.condition);
-        if self.result_expr_type != Type::Bool {
+        if self.result_expr_type != Type::Bool && !self.result_expr_type.is_poison() {
             self.push_error("Error: The expression in a while statement must have type bool.");
         }
         self.in_loop += 1;
@@ -417,7 +494,7 @@ impl Visitor for Interpreter {
                                                     .as_ref()
                                                     .unwrap());
                 // Rule 11: The expression in a return statement must have the same type as the declared result type of the enclosing method definition.
-                if self.result_expr_type != method_return_type {
+                if self.result_expr_type != method_return_type && !self.result_expr_type.is_poison() {
                     self.push_error(&format!("Error: The expression in a return statement must have the same type as the declared result type of the enclosing method definition."));
                 }
             }
@@ -441,6 +518,9 @@ impl Visitor for Interpreter {
                                                     .unwrap();
             self.visit_expression(rhs_expr);
             let rhs_type = self.result_expr_type.clone();
+            if lhs_type.is_poison() || rhs_type.is_poison() {
+                return;
+            }
             if lhs_type != rhs_type {
                 self.push_error(&format!("Error: The location and expression in an assignment must have the same type."));
             }
@@ -457,7 +537,7 @@ impl Visitor for Interpreter {
             }
         } else {
             // case we have ++, -- operation
-            if ![Type::Int, Type::Long].contains(&lhs_type) {
+            if ![Type::Int, Type::Long].contains(&lhs_type) && !lhs_type.is_poison() {
                 self.push_error(&format!("Error: The location in an increment or decrement assignment expression must have type int or long."));
             }
         }
@@ -466,19 +546,26 @@ impl Visitor for Interpreter {
     fn visit_method_call(&mut self, method_call: &AST::MethodCall) {
         // Rule 13: The ⟨id⟩ in a method statement must be a declared method or import.
         let method_name = method_call.name.name.as_str();
-        match self.find_var(method_name) {
+        match self.resolve_callable(&method_call.name) {
             Ok(result_entry) => {
                 match result_entry {
                     Entry::Method(method_entry) => { 
                         // Rule 8: If a method call is used as an expression, the method must return a result.
                         if self.in_expr > 0 {
-                            if self.result_expr_type == Type::Void {
+                            if method_entry.return_type == Type::Void {
                                 self.push_error(&format!("Error: Method {} used in an expression must return a non-void value", method_name));
                             }
                         }
+                        // the call's type is known even when its arguments are wrong
+                        let call_type = if self.in_expr > 0 && method_entry.return_type == Type::Void { Type::None } else { method_entry.return_type.clone() };
                         // Rule 7: The number and types of parameters in a method call (non-import) must be the same as the number and types of the declared parameters for the method.
                         if method_entry.param_count != method_call.args.len() {
                             self.push_error(&format!("Error: Method call to {} has incorrect number of parameters as: expected {} but got {}", method_name, method_entry.param_count, method_call.args.len()));
+                            // the arguments are still checked for errors of their own
+                            for arg_expr in method_call.args.iter() {
+                                self.visit_expression(arg_expr.as_ref());
+                            }
+                            self.result_expr_type = call_type;
                             return;
                         }
                         
@@ -487,14 +574,15 @@ impl Visitor for Interpreter {
                         for (i, arg_expr) in method_call.args.iter().enumerate() {
                             self.visit_expression(arg_expr.as_ref());
                             let expected_type = method_entry.param_list[i].var_type.clone();
-                            if self.result_expr_type != expected_type {
-                                self.push_error(&format!("Error: expected parameter {} in method call {} to have type {:?} but found type {:?}", i, method_name, expected_type, self.result_expr_type));
-                            }
-                            if [Type::IntArray, Type::LongArray, Type::BoolArray].contains(&self.result_expr_type) {
+                            if self.result_expr_type == Type::String {
+                                self.push_error(&format!("Error: String literals may not be used as parameters to non-import methods."));
+                            } else if [Type::IntArray, Type::LongArray, Type::BoolArray].contains(&self.result_expr_type) {
                                 self.push_error(&format!("Error: Array variables may not be used as parameters to non-import methods."));
+                            } else if self.result_expr_type != expected_type && !self.result_expr_type.is_poison() {
+                                self.push_error(&format!("Error: expected parameter {} in method call {} to have type {:?} but found type {:?}", i, method_name, expected_type, self.result_expr_type));
                             }
                         }
-                        self.result_expr_type = method_entry.return_type;
+                        self.result_expr_type = call_type;
 
                     },
                     Entry::Import(_) => {
@@ -503,7 +591,10 @@ impl Visitor for Interpreter {
                         }
                         self.result_expr_type = Type::Int;
                     },
-                    _ => self.push_error(&format!("Error: Identifier {} is not a declared method or import.", method_name)),
+                    _ => {
+                        self.push_error(&format!("Error: Identifier {} is not a declared method or import.", method_name));
+                        self.result_expr_type = Type::None;
+                    },
                 }
             },
             Err(()) => {
@@ -514,9 +605,8 @@ impl Visitor for Interpreter {
     }
 
     fn visit_len_call(&mut self, len_call: &AST::LenCall) {
-        let var_name = len_call.id.name.as_str();
         // Rule 15: The argument of the len operator must be an array variable.
-        match self.find_var(var_name) {
+        match self.resolve(&len_call.id) {
             Ok(id_entry) => {
                 match id_entry {
                     Entry::Array(_) => (),
@@ -530,7 +620,7 @@ impl Visitor for Interpreter {
 
     fn visit_int_cast(&mut self, int_cast: &AST::IntCast) {
         self.visit_expression(int_cast.cast_expr.as_ref());
-        if ![Type::Long, Type::Int].contains(&self.result_expr_type) {
+        if ![Type::Long, Type::Int].contains(&self.result_expr_type) && !self.result_expr_type.is_poison() {
             self.push_error("Error: The expression in an int cast must have type int or long.");
         }
         self.result_expr_type = Type::Int;
@@ -538,7 +628,7 @@ impl Visitor for Interpreter {
 
     fn visit_long_cast(&mut self, long_cast: &AST::LongCast) {
         self.visit_expression(long_cast.cast_expr.as_ref());
-        if ![Type::Long, Type::Int].contains(&self.result_expr_type) {
+        if ![Type::Long, Type::Int].contains(&self.result_expr_type) && !self.result_expr_type.is_poison() {
             self.push_error("Error: The expression in an long cast must have type int or long.");
         }
         self.result_expr_type = Type::Long;
@@ -550,17 +640,17 @@ impl Visitor for Interpreter {
         match unary_expression.op.as_str() {
             "!" => {
                 self.visit_expression(unary_expression.expr.as_ref());
-                if self.result_expr_type != Type::Bool {
+                if self.result_expr_type != Type::Bool && !self.result_expr_type.is_poison() {
                     self.push_error("Error: The operand of logical not ( ! ) must have type bool.");
                 }
                 self.result_expr_type = Type::Bool;
             },
             "-" => {
                 self.visit_expression(unary_expression.expr.as_ref());
-                if ![Type::Int, Type::Long].contains(&self.result_expr_type) {
+                if ![Type::Int, Type::Long].contains(&self.result_expr_type) && !self.result_expr_type.is_poison() {
                     self.push_error("Error: The operand of unary minus must have type int or long.");
+                    self.result_expr_type = Type::None;
                 }
-                self.result_expr_type = self.result_expr_type.clone();
             },
             _ => {
                 self.push_error(&format!("Error: invalid unary operator {} found.", unary_expression.op));
@@ -577,33 +667,45 @@ impl Visitor for Interpreter {
         let left_type = self.result_expr_type.clone();
         self.visit_expression(binary_expression.right_expr.as_ref());
         let right_type = self.result_expr_type.clone();
+        // an operand that already failed satisfies the checks, the other operand is still checked on its own
+        let poisoned = left_type.is_poison() || right_type.is_poison();
         match binary_expression.op.as_str() {
             "+" | "-" | "*" | "/" | "%" => {
-                if ![Type::Int, Type::Long].contains(&left_type) {
+                let valid_left = [Type::Int, Type::Long].contains(&left_type) || left_type.is_poison();
+                let valid_right = [Type::Int, Type::Long].contains(&right_type) || right_type.is_poison();
+                if !valid_left || !valid_right {
                     self.push_error(&format!("Error: The operands of the arithmetic operator {} must have type int or long.", binary_expression.op));
-                }
-                if left_type != right_type {
+                } else if !poisoned && left_type != right_type {
                     self.push_error(&format!("Error: The type of the operands of the arithmetic operator {} must be the same.", binary_expression.op));
                 }
-                self.result_expr_type = left_type.clone();
+                // the result has the operands' type, or that of the operand that did not fail
+                self.result_expr_type = match (left_type.is_poison(), right_type.is_poison()) {
+                    (false, false) if valid_left && left_type == right_type => left_type.clone(),
+                    (false, true) if valid_left => left_type.clone(),
+                    (true, false) if valid_right => right_type.clone(),
+                    _ => Type::None,
+                };
             },
             "<" | "<=" | ">" | ">=" => {
-                if ![Type::Int, Type::Long].contains(&left_type) {
+                let valid_left = [Type::Int, Type::Long].contains(&left_type) || left_type.is_poison();
+                let valid_right = [Type::Int, Type::Long].contains(&right_type) || right_type.is_poison();
+                if !valid_left || !valid_right {
                     self.push_error(&format!("Error: The operands of the comparison operator {} must have type int or long.", binary_expression.op));
-                }
-                if left_type != right_type {
+                } else if !poisoned && left_type != right_type {
                     self.push_error(&format!("Error: The type of the operands of the comparison operator {} must be the same.", binary_expression.op));
                 }
                 self.result_expr_type = Type::Bool;
             },
             "==" | "!=" => {
-                if left_type != right_type {
+                if !poisoned && left_type != right_type {
                     self.push_error(&format!("Error: The operands of the equality operator {} must have the same type.", binary_expression.op));
                 }
                 self.result_expr_type = Type::Bool;
             },
             "&&" | "||" => {
-                if left_type != Type::Bool || right_type != Type::Bool {
+                let valid_left = left_type == Type::Bool || left_type.is_poison();
+                let valid_right = right_type == Type::Bool || right_type.is_poison();
+                if !valid_left || !valid_right {
                     self.push_error(&format!("Error: The operands of the conditional operator {} must have type bool.", binary_expression.op));
                 }
                 self.result_expr_type = Type::Bool;
@@ -617,12 +719,12 @@ impl Visitor for Interpreter {
 
     fn visit_index_expression(&mut self, index_expression: &AST::IndexExpression) {
         // Rule 14: For all locations of the form ⟨id⟩[⟨expr⟩], the ⟨id⟩ must be an array variable and the type of ⟨expr⟩ must be int.
-        if let Ok(array_entry) = self.find_var(index_expression.id.name.as_str()) {
+        if let Ok(array_entry) = self.resolve(&index_expression.id) {
             match array_entry {
                 Entry::Array(_) => {
                     self.visit_identifier(index_expression.id.as_ref());
                     self.visit_expression(index_expression.idx_expr.as_ref());
-                    if self.result_expr_type != Type::Int {
+                    if self.result_expr_type != Type::Int && !self.result_expr_type.is_poison() {
                         self.push_error(&format!("Error: Index expression for array access to {} must have type int.", index_expression.id.name.as_str()));
                     }
                     match array_entry.get_type() {
@@ -632,9 +734,15 @@ impl Visitor for Interpreter {
                         _ => self.result_expr_type = Type::None,
                     }
                 },
-                _ => self.push_error(&format!("Error: Identifier {} must be an array variable.", index_expression.id.name.as_str())),
+                _ => {
+                    self.push_error(&format!("Error: Identifier {} must be an array variable.", index_expression.id.name.as_str()));
+                    // the index is still checked for errors of its own
+                    self.visit_expression(index_expression.idx_expr.as_ref());
+                    self.result_expr_type = Type::None;
+                },
             }
         } else {
+            self.visit_expression(index_expression.idx_expr.as_ref());
             self.result_expr_type = Type::None;
         }
     }
@@ -685,6 +793,9 @@ impl Visitor for Interpreter {
                 self.push_error("Error: invalid expression type found (check grammar).");
             },
         }
+        if let Some(node_id) = expression.node_id() {
+            self.expr_types.insert(node_id, self.result_expr_type.clone());
+        }
         self.in_expr -= 1;
     }
 
@@ -734,6 +845,9 @@ impl Visitor for Interpreter {
                 self.result_expr_type = Type::None;
             },
         }
+        if let Some(node_id) = location.node_id() {
+            self.expr_types.insert(node_id, self.result_expr_type.clone());
+        }
         self.in_location = false;
     }
 
@@ -748,7 +862,7 @@ impl Visitor for Interpreter {
             // read variable
             1 => {
                 // Rule 2: No identifier is used before it is declared
-                match self.find_var(id_name) {
+                match self.resolve(identifier) {
                     Ok(id_entry) => {
                         self.result_expr_type = id_entry.get_type();
                     },
@@ -760,7 +874,7 @@ impl Visitor for Interpreter {
             // write variable
             2 => {
                 // Rule 2: No identifier is used before it is declared
-                match self.find_var(id_name) {
+                match self.resolve(identifier) {
                     Ok(id_entry) => {
                         // Rule 23: const locations may not be assigned to
                         if id_entry.get_is_const() {
@@ -772,9 +886,11 @@ impl Visitor for Interpreter {
                             match id_entry {
                                 Entry::Method(_) => {
                                     self.push_error(&format!("Error: Id {} used as a location must have a declared local/global variable or formal parameter", id_name));
+                                    self.result_expr_type = Type::None;
                                 },
                                 Entry::Import(_) => {
                                     self.push_error(&format!("Error: Id {} used as a location must have a declared local/global variable or formal parameter", id_name));
+                                    self.result_expr_type = Type::None;
                                 },
                                 _ => (),
                             }
@@ -877,6 +993,10 @@ impl Visitor for Interpreter {
         self.result_expr_type = Type::Bool;
     }
 
+    fn visit_string_constant(&mut self, _string_constant: &AST::StringConstant) {
+        self.result_expr_type = Type::String;
+    }
+
     fn visit_char_constant(&mut self, _char_constant: &AST::CharConstant) {
         if self.checking_type {
             if self.init_type != Type::Int {
@@ -889,55 +1009,197 @@ impl Visitor for Interpreter {
     }
 }
 
-pub fn interpret_file(input: &std::path::PathBuf, debug: bool) -> Result<Vec<Box<Table>>, Vec<String>> {
+/*
+A parsed program that passed semantic analysis together with its symbol tables.
+Later stages (CFG, three address code) consume this so the file is only scanned, parsed and checked once.
+*/
+pub struct AnalyzedProgram {
+    pub ast: AST::Program,
+    pub scopes: Vec<Box<Table>>, // scopes[0] is the global scope
+    pub expr_types: HashMap<usize, Type>, // node id of each expression and location -> its type
+    pub resolutions: HashMap<usize, Entry>, // node id of each identifier use -> the entry it refers to
+    pub occurrences: Vec<Occurrence>, // every identifier, declarations included, sorted by line
+}
+
+impl AnalyzedProgram {
+    pub fn type_of(&self, expr: &AST::ASTNode) -> Option<&Type> {
+        return expr.node_id().and_then(|node_id| self.expr_types.get(&node_id));
+    }
+
+    // the declaration an identifier use refers to, shadowing already applied
+    pub fn entry_of(&self, identifier: &AST::Identifier) -> Option<&Entry> {
+        return self.resolutions.get(&identifier.node_id);
+    }
+
+    // the declaring occurrence of the entry with decl_id
+    pub fn declaration_of(&self, decl_id: usize) -> Option<&Occurrence> {
+        return self.occurrences.iter().find(|occurrence| occurrence.decl_id == decl_id && occurrence.status == 0);
+    }
+
+    // every occurrence resolving to the entry with decl_id, its declaration included
+    pub fn occurrences_of(&self, decl_id: usize) -> Vec<&Occurrence> {
+        return self.occurrences.iter().filter(|occurrence| occurrence.decl_id == decl_id).collect();
+    }
+
+    // look up a global variable, method or import by name
+    pub fn lookup_global(&self, name: &str) -> Option<&Entry> {
+        return self.scopes[0].entries.get(name);
+    }
+}
+
+fn run_interpreter(ast: &AST::Program, debug: bool) -> Interpreter {
+    let global_scope = Table {
+        method_return_type: Type::None,
+        entries: HashMap::new(),
+        scope_ind: 0,
+        parent_ind: None,
+    };
+    let scopes = vec![Box::new(global_scope)];
+
+    let mut interpreter = Interpreter {
+        scopes: scopes,
+        errors: vec![],
+        correct: true,
+        checking_type: false,
+        init_method: false,
+        init_type: Type::None,
+        in_loop: 0,
+        in_expr: 0,
+        in_location: false,
+        var_count: 0,
+        cur_scope_ind: 0,
+        result_expr_type: Type::None,
+        expr_types: HashMap::new(),
+        resolutions: HashMap::new(),
+        occurrences: vec![],
+        undeclared: HashSet::new(),
+        debug: debug,
+    };
+
+    ast.accept(&mut interpreter);
+    return interpreter;
+}
+
+// type check an already parsed program, returning its scopes
+pub fn interpret_program(ast: &AST::Program, debug: bool) -> Result<Vec<Box<Table>>, Vec<String>> {
+    let interpreter = run_interpreter(ast, debug);
+    if interpreter.correct {
+        return Ok(interpreter.scopes);
+    } else {
+        return Err(interpreter.errors);
+    }
+}
+
+// type check the program, keeping the AST alongside the scopes and side tables
+pub fn analyze_program(ast: AST::Program, debug: bool) -> Result<AnalyzedProgram, Vec<String>> {
+    let interpreter = run_interpreter(&ast, debug);
+    if !interpreter.correct {
+        return Err(interpreter.errors);
+    }
+    let mut occurrences = interpreter.occurrences;
+    occurrences.sort_by_key(|occurrence| occurrence.line_num);
+    return Ok(AnalyzedProgram {
+        ast: ast,
+        scopes: interpreter.scopes,
+        expr_types: interpreter.expr_types,
+        resolutions: interpreter.resolutions,
+        occurrences: occurrences,
+    });
+}
+
+pub fn analyze_file(input: &std::path::PathBuf, debug: bool) -> Result<AnalyzedProgram, Vec<String>> {
     let _input = std::fs::read_to_string(input).expect("Filename is incorrect.");
     match parse_file(input) {
-        Ok(ast) => {
-            let global_scope = Table {
-                method_return_type: Type::None,
-                entries: HashMap::new(),
-                scope_ind: 0,
-                parent_ind: None,
-            };
-            let scopes = vec![Box::new(global_scope)];
-
-            let mut interpreter = Interpreter {
-                scopes: scopes,
-                errors: vec![],
-                correct: true,
-                checking_type: false,
-                init_method: false,
-                init_type: Type::None,
-                in_loop: 0,
-                in_expr: 0,
-                in_location: false,
-                var_count: 0,
-                cur_scope_ind: 0,
-                result_expr_type: Type::None,
-                debug: debug,
-            };
+        Ok(ast) => return analyze_program(ast, debug),
+        Err(errors) => return Err(errors),
+    }
+}
 
-            ast.accept(&mut interpreter);
+/*
+Type checks and lints the program. Denied lints fail it the same way semantic errors do, so every
+entry point agrees on which programs are legal. The warnings are returned with the program.
+*/
+pub fn check_program(ast: AST::Program, lints: &LintConfig, debug: bool) -> Result<(AnalyzedProgram, Vec<String>), Vec<String>> {
+    let analyzed_program = analyze_program(ast, debug)?;
+    let report = lint_program(&analyzed_program, lints);
+    if !report.errors.is_empty() {
+        return Err(report.errors);
+    }
+    return Ok((analyzed_program, report.warnings));
+}
 
-            // create final symbol table
+pub fn check_file(input: &std::path::PathBuf, lints: &LintConfig, debug: bool) -> Result<(AnalyzedProgram, Vec<String>), Vec<String>> {
+    let _input = std::fs::read_to_string(input).expect("Filename is incorrect.");
+    match parse_file(input) {
+        Ok(ast) => return check_program(ast, lints, debug),
+        Err(errors) => return Err(errors),
+    }
+}
 
-            if interpreter.correct {
-                return Ok(interpreter.scopes);
-            } else {
-                return Err(interpreter.errors);
+// parse and type check decaf source held in memory
+pub fn interpret_source(source: &str, debug: bool) -> Result<Vec<Box<Table>>, Vec<String>> {
+    match parse_source(source) {
+        Ok(ast) => return interpret_program(&ast, debug),
+        Err(errors) => return Err(errors),
+    }
+}
+
+pub fn interpret_file(input: &std::path::PathBuf, debug: bool) -> Result<Vec<Box<Table>>, Vec<String>> {
+    return analyze_file(input, debug).map(|analyzed_program| analyzed_program.scopes);
+}
+
+pub fn interpret(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, lints: &LintConfig, debug: bool) {
+    match check_file(input, lints, debug) {
+        Ok((_, warnings)) => {
+            for warning in warnings {
+                eprintln!("{}", warning);
             }
+            writeln!(writer, "Interpreted successfully.").unwrap();
+            std::process::exit(0);
         }
-
         Err(errors) => {
-            return Err(errors);
+            for error in errors {
+                writeln!(writer, "{}", error).unwrap();
+            }
+            std::process::exit(1);
         }
     }
 }
 
-pub fn interpret(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, debug: bool) {
-    match interpret_file(input, debug) {
-        Ok(_) => {
-            writeln!(writer, "Interpreted successfully.").unwrap();
+// one line per identifier: where it is, how it is used and the declaration it resolves to
+pub fn dump_symbols(analyzed_program: &AnalyzedProgram) -> String {
+    let mut output = String::new();
+    for occurrence in &analyzed_program.occurrences {
+        let usage = match occurrence.status {
+            0 => "declare",
+            1 => "read",
+            _ => "write",
+        };
+        let entry = &analyzed_program.resolutions[&occurrence.node_id];
+        let declared_line = match analyzed_program.declaration_of(occurrence.decl_id) {
+            Some(declaration) => declaration.line_num.to_string(),
+            None => "?".to_string(),
+        };
+        let kind = if entry.get_is_const() { format!("const {}", entry.get_kind()) } else { entry.get_kind().to_string() };
+        // const variables show the value later stages propagate
+        let value = match entry {
+            Entry::Var(VarEntry { const_value: Some(const_value), .. }) => {
+                let mut printer = ParserPrinter::new();
+                const_value.accept(&mut printer);
+                format!(" = {}", printer.output)
+            },
+            _ => String::new(),
+        };
+        output.push_str(&format!("{}: {} {} -> #{} {} {:?}{} in scope {} declared on line {}\n",
+            occurrence.line_num, occurrence.name, usage, occurrence.decl_id, kind, entry.get_type(), value, occurrence.scope, declared_line));
+    }
+    return output;
+}
+
+pub fn symbols(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>, debug: bool) {
+    match analyze_file(input, debug) {
+        Ok(analyzed_program) => {
+            write!(writer, "{}", dump_symbols(&analyzed_program)).unwrap();
             std::process::exit(0);
         }
         Err(errors) => {
@@ -947,4 +1209,181 @@ pub fn interpret(input: &std::path::PathBuf, mut writer: Box<dyn std::io::Write>
             std::process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze_program, dump_symbols, interpret_source, AnalyzedProgram};
+    use crate::irs::cfg::cfg_blocks::Block;
+    use crate::irs::cfg::cfg_creator::construct_program_graph;
+    use crate::parser::parser::parse_source;
+    use crate::parser::AST;
+    use crate::semantics::symbol_table::Type;
+
+    // x is declared three times: as a global, as a parameter and as a local of the if block
+    const SHADOWING: &str = "int x;
+int f(int x) {
+    int y;
+    y = x;
+    if (y > 0) {
+        int x;
+        x = y;
+    }
+    return x;
+}
+void main() {
+    x = f(1);
+}
+";
+
+    fn analyze(src: &str) -> AnalyzedProgram {
+        let ast = parse_source(src).expect("test program should parse");
+        return analyze_program(ast, false).expect("test program should pass semantic checks");
+    }
+
+    fn semantic_errors(src: &str) -> Vec<String> {
+        return interpret_source(src, false).err().unwrap_or_default();
+    }
+
+    #[test]
+    fn string_literal_arguments_to_methods() {
+        let errors = semantic_errors("void f(int a) { }\nvoid main() { f(\"hi\"); }");
+        assert_eq!(errors, vec!["Error: String literals may not be used as parameters to non-import methods."]);
+        assert!(interpret_source("import printf;\nvoid main() { printf(\"hi %d\", 1); }", false).is_ok());
+    }
+
+    #[test]
+    fn array_arguments_are_reported_once() {
+        let errors = semantic_errors("int a[3];\nvoid f(int x) { }\nvoid main() { f(a); }");
+        assert_eq!(errors, vec!["Error: Array variables may not be used as parameters to non-import methods."]);
+    }
+
+    #[test]
+    fn invalid_right_operand_is_reported() {
+        let errors = semantic_errors("void main() { int x; x = 1 + true; }");
+        assert_eq!(errors, vec!["Error: The operands of the arithmetic operator + must have type int or long."]);
+        let errors = semantic_errors("void main() { bool b; b = 1 < false; }");
+        assert_eq!(errors, vec!["Error: The operands of the comparison operator < must have type int or long."]);
+    }
+
+    #[test]
+    fn poisoned_right_operand_is_not_reported_again() {
+        // only the undeclared name is reported, not the addition or the assignment it feeds
+        let errors = semantic_errors("void main() { int x; x = 1 + y; x = x * (y - 2); }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Error: Identifier y is used before it is declared."));
+    }
+
+    #[test]
+    fn arguments_of_a_call_with_the_wrong_arity_are_checked() {
+        let errors = semantic_errors("void f(int a) { }\nvoid main() { f(1, z); }");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Error: Method call to f has incorrect number of parameters"));
+        assert!(errors[1].starts_with("Error: Identifier z is used before it is declared."));
+    }
+
+    #[test]
+    fn index_of_a_non_array_is_checked() {
+        let errors = semantic_errors("void main() { int x, y; y = x[z]; y = x[1 + true]; }");
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0], "Error: Identifier x must be an array variable.");
+        assert!(errors[1].starts_with("Error: Identifier z is used before it is declared."));
+        assert_eq!(errors[2], "Error: Identifier x must be an array variable.");
+        assert_eq!(errors[3], "Error: The operands of the arithmetic operator + must have type int or long.");
+    }
+
+    #[test]
+    fn identifiers_resolve_to_their_declaration() {
+        let analyzed_program = analyze(SHADOWING);
+        for occurrence in &analyzed_program.occurrences {
+            let declaration = analyzed_program.declaration_of(occurrence.decl_id).expect("every entry has a declaration");
+            assert_eq!(declaration.name, occurrence.name);
+            assert_eq!(declaration.scope, occurrence.scope);
+            let entry = &analyzed_program.resolutions[&occurrence.node_id];
+            assert_eq!((entry.get_id(), entry.get_scope()), (occurrence.decl_id, occurrence.scope));
+        }
+    }
+
+    #[test]
+    fn shadowed_names_resolve_to_the_innermost_declaration() {
+        let analyzed_program = analyze(SHADOWING);
+        let x: Vec<_> = analyzed_program.occurrences.iter().filter(|occurrence| occurrence.name == "x").collect();
+        let lines: Vec<(usize, i32)> = x.iter().map(|occurrence| (occurrence.line_num, occurrence.status)).collect();
+        assert_eq!(lines, vec![(1, 0), (2, 0), (4, 1), (6, 0), (7, 2), (9, 1), (12, 2)]);
+        let (global, param, local) = (x[0], x[1], x[3]);
+        assert_eq!(global.scope, 0);
+        assert!(param.scope != 0 && local.scope != param.scope);
+        assert_eq!(analyzed_program.occurrences_of(global.decl_id).len(), 2);
+        assert_eq!(x[6].decl_id, global.decl_id);
+        assert_eq!((x[2].decl_id, x[5].decl_id), (param.decl_id, param.decl_id));
+        assert_eq!(x[4].decl_id, local.decl_id);
+    }
+
+    #[test]
+    fn cfg_statements_keep_their_node_ids() {
+        let analyzed_program = analyze(SHADOWING);
+        let program_graph = construct_program_graph(&analyzed_program);
+        let mut assignments = 0;
+        for block in &program_graph.method_graphs["f"].nodes {
+            let statements = match block {
+                Block::Basic(basic_block) => &basic_block.statements,
+                _ => continue,
+            };
+            for statement in statements {
+                let (identifier, expr) = match statement.as_ref() {
+                    AST::ASTNode::Assignment(assignment) => match (assignment.assign_var.as_ref(), assignment.expr.as_ref()) {
+                        (AST::ASTNode::Identifier(identifier), Some(expr)) => (identifier, expr),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                // the copy in the CFG finds the same entries as the identifier in the AST
+                let occurrence = analyzed_program.occurrences.iter()
+                    .find(|occurrence| occurrence.line_num == identifier.line_num && occurrence.name == identifier.name)
+                    .expect("every assigned identifier is an occurrence");
+                assert_eq!(occurrence.node_id, identifier.node_id);
+                assert_eq!(analyzed_program.entry_of(identifier).map(|entry| entry.get_id()), Some(occurrence.decl_id));
+                assert_eq!(analyzed_program.type_of(expr), Some(&Type::Int));
+                assignments += 1;
+            }
+        }
+        // y = x and the shadowing x = y
+        assert_eq!(assignments, 2);
+    }
+
+    #[test]
+    fn symbols_dump_format() {
+        let analyzed_program = analyze("const int LIMIT = 10;
+int total;
+int scale(int x) {
+    const long FACTOR = -2L;
+    return x * 2;
+}
+void main() {
+    const bool DONE = true;
+    if (DONE) {
+        int step;
+        step = scale(LIMIT);
+        total = step;
+    }
+}
+");
+        assert_eq!(dump_symbols(&analyzed_program), "\
+1: LIMIT declare -> #0 const var Int = 10 in scope 0 declared on line 1
+2: total declare -> #1 var Int in scope 0 declared on line 2
+3: scale declare -> #2 method Int in scope 0 declared on line 3
+3: x declare -> #4 var Int in scope 1 declared on line 3
+4: FACTOR declare -> #5 const var Long = -2L in scope 1 declared on line 4
+5: x read -> #4 var Int in scope 1 declared on line 3
+7: main declare -> #6 method Void in scope 0 declared on line 7
+8: DONE declare -> #7 const var Bool = true in scope 2 declared on line 8
+9: DONE read -> #7 const var Bool = true in scope 2 declared on line 8
+10: step declare -> #8 var Int in scope 3 declared on line 10
+11: step write -> #8 var Int in scope 3 declared on line 10
+11: scale read -> #2 method Int in scope 0 declared on line 3
+11: LIMIT read -> #0 const var Int = 10 in scope 0 declared on line 1
+12: total write -> #1 var Int in scope 0 declared on line 2
+12: step read -> #8 var Int in scope 3 declared on line 10
+");
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use super::super::parser::AST;
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -19,6 +20,33 @@ impl Entry {
         }
     }
 
+    pub fn get_id(&self) -> usize {
+        match self {
+            Entry::Var(v) => v.id,
+            Entry::Array(a) => a.id,
+            Entry::Method(m) => m.id,
+            Entry::Import(i) => i.id,
+        }
+    }
+
+    pub fn get_scope(&self) -> usize {
+        match self {
+            Entry::Var(v) => v.scope,
+            Entry::Array(a) => a.scope,
+            Entry::Method(m) => m.scope,
+            Entry::Import(i) => i.scope,
+        }
+    }
+
+    pub fn get_kind(&self) -> &'static str {
+        match self {
+            Entry::Var(_) => "var",
+            Entry::Array(_) => "array",
+            Entry::Method(_) => "method",
+            Entry::Import(_) => "import",
+        }
+    }
+
     pub fn get_type(&self) -> Type {
         match self {
             Entry::Var(v) => v.var_type.clone(),
@@ -40,15 +68,25 @@ pub enum Type {
     IntArray,
     LongArray,
     BoolArray,
+    String, // only string literals, which can only be arguments to a method call
     None, // default value for error propogation
 }
 
+impl Type {
+    // None is the type of an expression whose error was already reported, it satisfies every check
+    // so a single mistake does not cascade into errors on the enclosing expressions
+    pub fn is_poison(&self) -> bool {
+        return *self == Type::None;
+    }
+}
+
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct VarEntry {
     pub name: String,
     pub var_type: Type,
     pub is_const: bool,
+    pub const_value: Option<AST::ASTNode>, // initializer literal of a const variable
     pub scope: usize,
     pub id: usize,
 }
@@ -85,6 +123,20 @@ pub struct ImportEntry {
     pub id: usize,
 }
 
+/*
+One identifier in the source and the declaration it resolves to (itself for declarations)
+*/
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Occurrence {
+    pub node_id: usize, // node id of the identifier
+    pub name: String,
+    pub line_num: usize,
+    pub status: i32, // 0 for declare, 1 for read, 2 for write, as on the identifier
+    pub decl_id: usize, // id of the resolved entry
+    pub scope: usize, // index of the scope declaring the entry
+}
+
 #[derive(Clone)]
 pub struct Table {
     pub method_return_type: Type,
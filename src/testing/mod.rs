@@ -0,0 +1,3 @@
+pub mod runner;
+pub mod snapshot;
+pub mod report;
\ No newline at end of file
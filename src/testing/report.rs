@@ -0,0 +1,226 @@
+use super::runner::{Expectation, Outcome, Stage, TestResult};
+
+use std::path::Path;
+
+/*
+Machine readable test reports. JUnit XML has one testsuite per stage with a testcase per input,
+JSON has a summary object and one entry per test in discovery order.
+*/
+
+const STAGES: [Stage; 3] = [Stage::Scan, Stage::Parse, Stage::Inter];
+
+fn expectation_name(expectation: &Expectation) -> &'static str {
+    match expectation {
+        Expectation::Legal => "legal",
+        Expectation::Illegal => "illegal",
+        Expectation::Output(_) => "output",
+    }
+}
+
+fn status_name(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Pass => "pass",
+        Outcome::Fail(_) => "fail",
+        Outcome::Timeout => "timeout",
+        Outcome::Panic(_) => "panic",
+    }
+}
+
+fn outcome_message(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Pass => String::new(),
+        Outcome::Fail(reason) => reason.clone(),
+        Outcome::Timeout => "test timed out".to_string(),
+        Outcome::Panic(message) => format!("stage panicked: {}", message),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // characters not allowed in XML 1.0
+            c if (c as u32) < 0x20 && c != '\n' && c != '\r' && c != '\t' => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub fn junit_report(results: &[TestResult]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let total_time: f64 = results.iter().map(|result| result.duration.as_secs_f64()).sum();
+    let total_failures = results.iter().filter(|result| matches!(result.outcome, Outcome::Fail(_))).count();
+    let total_errors = results.iter().filter(|result| matches!(result.outcome, Outcome::Timeout | Outcome::Panic(_))).count();
+    xml.push_str(&format!("<testsuites name=\"decaf\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        results.len(), total_failures, total_errors, total_time));
+
+    for stage in STAGES {
+        let stage_results: Vec<&TestResult> = results.iter().filter(|result| result.case.stage == stage).collect();
+        if stage_results.is_empty() {
+            continue;
+        }
+        let time: f64 = stage_results.iter().map(|result| result.duration.as_secs_f64()).sum();
+        let failures = stage_results.iter().filter(|result| matches!(result.outcome, Outcome::Fail(_))).count();
+        let errors = stage_results.iter().filter(|result| matches!(result.outcome, Outcome::Timeout | Outcome::Panic(_))).count();
+        xml.push_str(&format!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            stage.dir_name(), stage_results.len(), failures, errors, time));
+
+        for result in stage_results {
+            let name = result.case.input.file_name().unwrap_or_default().to_string_lossy();
+            xml.push_str(&format!("    <testcase classname=\"{}.{}\" name=\"{}\" file=\"{}\" time=\"{:.3}\">\n",
+                stage.dir_name(), expectation_name(&result.case.expectation), escape_xml(&name),
+                escape_xml(&result.case.input.display().to_string()), result.duration.as_secs_f64()));
+            // failures are wrong results, errors are tests that did not finish
+            let tag = match result.outcome {
+                Outcome::Pass => None,
+                Outcome::Fail(_) => Some("failure"),
+                Outcome::Timeout | Outcome::Panic(_) => Some("error"),
+            };
+            if let Some(tag) = tag {
+                let message = outcome_message(&result.outcome);
+                let summary = message.lines().next().unwrap_or_default();
+                xml.push_str(&format!("      <{} message=\"{}\" type=\"{}\">{}</{}>\n",
+                    tag, escape_xml(summary), status_name(&result.outcome), escape_xml(&message), tag));
+            }
+            if !result.output.is_empty() {
+                xml.push_str(&format!("      <system-out>{}</system-out>\n", escape_xml(&result.output)));
+            }
+            if !result.error_output.is_empty() {
+                xml.push_str(&format!("      <system-err>{}</system-err>\n", escape_xml(&result.error_output)));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+pub fn json_report(results: &[TestResult]) -> String {
+    let passed = results.iter().filter(|result| matches!(result.outcome, Outcome::Pass)).count();
+    let mut json = String::from("{\n");
+    json.push_str(&format!("  \"summary\": {{\"total\": {}, \"passed\": {}, \"failed\": {}}},\n", results.len(), passed, results.len() - passed));
+    json.push_str("  \"tests\": [");
+    for (i, result) in results.iter().enumerate() {
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        json.push_str(&format!("    {{\"stage\": {}, \"file\": {}, \"expectation\": {}, \"status\": {}, \"duration_ms\": {:.3}, \"message\": {}, \"output\": {}, \"error_output\": {}}}",
+            escape_json(result.case.stage.dir_name()),
+            escape_json(&result.case.input.display().to_string()),
+            escape_json(expectation_name(&result.case.expectation)),
+            escape_json(status_name(&result.outcome)),
+            result.duration.as_secs_f64() * 1000.0,
+            escape_json(&outcome_message(&result.outcome)),
+            escape_json(&result.output),
+            escape_json(&result.error_output)));
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
+
+pub fn write_report(path: &Path, report: &str) {
+    if let Err(e) = std::fs::write(path, report) {
+        eprintln!("Failed to write test report {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_json, escape_xml, json_report, junit_report};
+    use crate::testing::runner::{Expectation, Outcome, Stage, TestCase, TestResult};
+
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn result(stage: Stage, name: &str, outcome: Outcome, output: &str, error_output: &str) -> TestResult {
+        TestResult {
+            case: TestCase {
+                stage: stage,
+                input: PathBuf::from(format!("suite/{}", name)),
+                expectation: Expectation::Legal,
+            },
+            outcome: outcome,
+            output: output.to_string(),
+            error_output: error_output.to_string(),
+            duration: Duration::from_millis(2),
+        }
+    }
+
+    // one result of each outcome, parse and inter tests included
+    fn results() -> Vec<TestResult> {
+        vec![
+            result(Stage::Scan, "pass.dcf", Outcome::Pass, "1 IDENTIFIER x\n", ""),
+            result(Stage::Parse, "fail.dcf", Outcome::Fail("expected success, got errors:\nLine: 1 - bad <token>".to_string()), "Line: 1 - bad <token>\n", ""),
+            result(Stage::Inter, "slow.dcf", Outcome::Timeout, "", ""),
+            result(Stage::Inter, "crash.dcf", Outcome::Panic("panicked at \"index\" & more".to_string()), "", "thread 'main' panicked\n"),
+        ]
+    }
+
+    #[test]
+    fn xml_escapes_markup_and_control_characters() {
+        assert_eq!(escape_xml("a < b && c > \"d\" 'e'"), "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &apos;e&apos;");
+        assert_eq!(escape_xml("tab\tline\nbell\u{7}nul\0"), "tab\tline\nbell\\x07nul\\x00");
+    }
+
+    #[test]
+    fn json_escapes_quotes_and_control_characters() {
+        assert_eq!(escape_json("say \"hi\" \\ <a> & b"), "\"say \\\"hi\\\" \\\\ <a> & b\"");
+        assert_eq!(escape_json("line\nret\rtab\tbell\u{7}"), "\"line\\nret\\rtab\\tbell\\u0007\"");
+    }
+
+    #[test]
+    fn junit_report_has_an_element_per_outcome() {
+        let xml = junit_report(&results());
+        assert!(xml.contains("<testsuites name=\"decaf\" tests=\"4\" failures=\"1\" errors=\"2\""));
+        assert!(xml.contains("<testsuite name=\"scanner\" tests=\"1\" failures=\"0\" errors=\"0\""));
+        assert!(xml.contains("<testsuite name=\"semantics\" tests=\"2\" failures=\"0\" errors=\"2\""));
+        // only the first line of a failure goes in its message
+        assert!(xml.contains("<failure message=\"expected success, got errors:\" type=\"fail\">expected success, got errors:\nLine: 1 - bad &lt;token&gt;</failure>"));
+        assert!(xml.contains("<error message=\"test timed out\" type=\"timeout\">test timed out</error>"));
+        assert!(xml.contains("<error message=\"stage panicked: panicked at &quot;index&quot; &amp; more\" type=\"panic\">"));
+        assert!(xml.contains("<system-out>1 IDENTIFIER x\n</system-out>"));
+        assert!(xml.contains("<system-err>thread &apos;main&apos; panicked\n</system-err>"));
+        // passing tests have no failure or error, and empty streams are left out
+        let pass = &xml[xml.find("name=\"pass.dcf\"").unwrap()..xml.find("name=\"fail.dcf\"").unwrap()];
+        assert!(!pass.contains("<failure") && !pass.contains("<error") && !pass.contains("<system-err>"));
+        assert_eq!(xml.matches("<system-out>").count(), 2);
+    }
+
+    #[test]
+    fn json_report_has_a_status_per_outcome() {
+        let json = json_report(&results());
+        assert!(json.contains("\"summary\": {\"total\": 4, \"passed\": 1, \"failed\": 3}"));
+        assert!(json.contains("\"file\": \"suite/pass.dcf\", \"expectation\": \"legal\", \"status\": \"pass\""));
+        assert!(json.contains("\"status\": \"fail\""));
+        assert!(json.contains("\"status\": \"timeout\", \"duration_ms\": 2.000, \"message\": \"test timed out\""));
+        assert!(json.contains("\"status\": \"panic\""));
+        assert!(json.contains("\"message\": \"stage panicked: panicked at \\\"index\\\" & more\""));
+        assert!(json.contains("\"output\": \"Line: 1 - bad <token>\\n\""));
+        assert!(json.contains("\"error_output\": \"thread 'main' panicked\\n\""));
+        // tests are listed in the order they were given
+        assert!(json.find("pass.dcf").unwrap() < json.find("fail.dcf").unwrap());
+        assert!(json.find("slow.dcf").unwrap() < json.find("crash.dcf").unwrap());
+    }
+}
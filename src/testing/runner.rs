@@ -0,0 +1,403 @@
+use super::snapshot::SnapshotMode;
+use super::report::{json_report, junit_report, write_report};
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/*
+Test runner for the tests24 suites. Tests are discovered by directory layout:
+    scanner/input/NAME.dcf with expected tokens in scanner/output/NAME.out (inputs named invalid must error)
+    parser/legal, parser/illegal, semantics/legal and semantics/illegal hold .dcf files
+Each test runs its stage in a child compiler process (the scan, parse or inter target, the last with
+the default lint levels), which is killed once it times out.
+With snapshots enabled, a test also has to match the stored outputs of every stage (see snapshot.rs),
+which a second child checks with the snapshot target within what is left of the test's timeout.
+*/
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum Stage {
+    Scan,
+    Parse,
+    Inter,
+}
+
+impl Stage {
+    // name of the suite directory holding this stage's tests
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            Stage::Scan => "scanner",
+            Stage::Parse => "parser",
+            Stage::Inter => "semantics",
+        }
+    }
+
+    // the compiler target that runs this stage
+    fn target_name(&self) -> &'static str {
+        match self {
+            Stage::Scan => "scan",
+            Stage::Parse => "parse",
+            Stage::Inter => "inter",
+        }
+    }
+
+    fn from_dir_name(name: &str) -> Option<Stage> {
+        match name {
+            "scanner" => Some(Stage::Scan),
+            "parser" => Some(Stage::Parse),
+            "semantics" => Some(Stage::Inter),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub enum Expectation {
+    Legal, // stage succeeds
+    Illegal, // stage reports an error
+    Output(PathBuf), // stage succeeds and its output matches the file
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct TestCase {
+    pub stage: Stage,
+    pub input: PathBuf,
+    pub expectation: Expectation,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Fail(String), // reason, including a diff for output mismatches
+    Timeout,
+    Panic(String),
+}
+
+#[derive(Debug)]
+pub struct TestResult {
+    pub case: TestCase,
+    pub outcome: Outcome,
+    pub output: String, // what the stage wrote to stdout, tokens or diagnostics
+    pub error_output: String, // what the stage wrote to stderr, lint warnings or a panic message
+    pub duration: Duration,
+}
+
+pub struct RunnerOptions {
+    pub jobs: Option<usize>, // defaults to the number of cores
+    pub timeout_secs: u64,
+    pub snapshot_mode: SnapshotMode,
+    pub junit: Option<PathBuf>, // JUnit XML report path
+    pub json: Option<PathBuf>, // JSON report path
+}
+
+pub fn discover_tests(root: &Path) -> Vec<TestCase> {
+    let mut cases: Vec<TestCase> = vec![];
+    for entry in WalkDir::new(root).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().map_or(true, |ext| ext != "dcf") {
+            continue;
+        }
+        let case_dir = match path.parent() {
+            Some(dir) => dir,
+            None => continue,
+        };
+        let stage = match case_dir.parent().and_then(|dir| dir.file_name()).and_then(|name| Stage::from_dir_name(&name.to_string_lossy())) {
+            Some(stage) => stage,
+            None => continue,
+        };
+        let case_kind = case_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let expectation = match (stage, case_kind.as_str()) {
+            (Stage::Scan, "input") if file_name.contains("invalid") => Expectation::Illegal,
+            (Stage::Scan, "input") => Expectation::Output(case_dir.with_file_name("output").join(path.with_extension("out").file_name().unwrap())),
+            (Stage::Parse, "legal") | (Stage::Inter, "legal") => Expectation::Legal,
+            (Stage::Parse, "illegal") | (Stage::Inter, "illegal") => Expectation::Illegal,
+            _ => continue,
+        };
+        cases.push(TestCase {
+            stage: stage,
+            input: path.to_path_buf(),
+            expectation: expectation,
+        });
+    }
+    cases
+}
+
+// polls the child until it exits, killing it once timeout has passed
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) if start.elapsed() < timeout => std::thread::sleep(Duration::from_millis(5)),
+            _ => {
+                child.kill().ok();
+                child.wait().ok();
+                return None;
+            },
+        }
+    }
+}
+
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_string(&mut text).ok();
+        }
+        text
+    })
+}
+
+// what a child compiler process reported
+struct ChildOutput {
+    result: Result<bool, Outcome>, // whether the child succeeded, or why it did not finish
+    stdout: String,
+    stderr: String,
+}
+
+/*
+Runs the compiler with args on the input in a child process. A child that runs past the timeout is
+killed, so a hung test never outlives its slot, and a child that panics is reported as a panic.
+Whatever the child wrote is kept either way.
+*/
+fn run_compiler(compiler: &Path, args: &[&str], input: &Path, timeout: Duration) -> ChildOutput {
+    let spawned = Command::new(compiler)
+        .args(args)
+        .arg(input)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return ChildOutput {
+            result: Err(Outcome::Fail(format!("could not start {}: {}", compiler.display(), e))),
+            stdout: String::new(),
+            stderr: String::new(),
+        },
+    };
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let status = wait_with_timeout(&mut child, timeout);
+    let output = stdout.join().unwrap_or_default();
+    let errors = stderr.join().unwrap_or_default();
+    let result = match status {
+        None => Err(Outcome::Timeout),
+        // rust exits with 101 when the process panics
+        Some(status) if status.code() == Some(101) => {
+            let message = errors.lines().find(|line| line.contains("panicked")).unwrap_or("unknown panic");
+            Err(Outcome::Panic(message.to_string()))
+        },
+        Some(status) if status.code().is_none() => Err(Outcome::Fail(format!("stage was terminated by a signal ({})", status))),
+        Some(status) => Ok(status.success()),
+    };
+    ChildOutput {
+        result: result,
+        stdout: output,
+        stderr: errors,
+    }
+}
+
+// runs the stage's target on the input
+fn run_stage(compiler: &Path, stage: Stage, input: &Path, timeout: Duration) -> ChildOutput {
+    run_compiler(compiler, &["--target", stage.target_name()], input, timeout)
+}
+
+// checks (or with bless, updates) the snapshots of the input with the snapshot target
+fn run_snapshots(compiler: &Path, input: &Path, timeout: Duration, snapshot_mode: SnapshotMode) -> Outcome {
+    let args: &[&str] = match snapshot_mode {
+        SnapshotMode::Bless => &["--target", "snapshot", "--bless"],
+        _ => &["--target", "snapshot"],
+    };
+    let child_output = run_compiler(compiler, args, input, timeout);
+    match child_output.result {
+        Ok(true) => Outcome::Pass,
+        Ok(false) => Outcome::Fail(child_output.stdout),
+        Err(outcome) => outcome,
+    }
+}
+
+// outcome of the case along with what the stage wrote to stdout and stderr, the stage and snapshots share the timeout
+fn check_case(compiler: &Path, case: &TestCase, timeout: Duration, snapshot_mode: SnapshotMode) -> (Outcome, String, String) {
+    let start = Instant::now();
+    let stage_output = run_stage(compiler, case.stage, &case.input, timeout);
+    let outcome = match stage_output.result {
+        Ok(success) => check_expectation(case, success, &stage_output.stdout),
+        Err(outcome) => outcome,
+    };
+    if !matches!(outcome, Outcome::Pass) || snapshot_mode == SnapshotMode::Off {
+        return (outcome, stage_output.stdout, stage_output.stderr);
+    }
+    // the snapshots run every stage, including those after the one under test, so they get a child of their own
+    let outcome = run_snapshots(compiler, &case.input, timeout.saturating_sub(start.elapsed()), snapshot_mode);
+    (outcome, stage_output.stdout, stage_output.stderr)
+}
+
+fn check_expectation(case: &TestCase, success: bool, output: &str) -> Outcome {
+    match &case.expectation {
+        Expectation::Legal if !success => Outcome::Fail(format!("expected success, got errors:\n{}", output)),
+        Expectation::Illegal if success => Outcome::Fail("expected an error, but the input was accepted".to_string()),
+        Expectation::Output(expected_path) => {
+            let expected = match std::fs::read_to_string(expected_path) {
+                Ok(expected) => expected,
+                Err(e) => return Outcome::Fail(format!("could not read {}: {}", expected_path.display(), e)),
+            };
+            if !success {
+                Outcome::Fail(format!("expected success, got errors:\n{}", output))
+            } else if expected != output {
+                Outcome::Fail(format!("output differs from {}:\n{}", expected_path.display(), diff_lines(&expected, output)))
+            } else {
+                Outcome::Pass
+            }
+        },
+        _ => Outcome::Pass,
+    }
+}
+
+// compiler is the binary the stages are run with, normally the running compiler itself
+pub fn run_tests(compiler: &Path, cases: &[TestCase], jobs: usize, timeout: Duration, snapshot_mode: SnapshotMode) -> Vec<TestResult> {
+    let cases = Arc::new(cases.to_vec());
+    let compiler = Arc::new(compiler.to_path_buf());
+    let next_case = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    let mut workers = vec![];
+    for _ in 0..jobs.max(1) {
+        let cases = Arc::clone(&cases);
+        let compiler = Arc::clone(&compiler);
+        let next_case = Arc::clone(&next_case);
+        let sender = sender.clone();
+        workers.push(std::thread::spawn(move || {
+            loop {
+                let ind = next_case.fetch_add(1, Ordering::SeqCst);
+                if ind >= cases.len() {
+                    break;
+                }
+                let start = Instant::now();
+                let (outcome, output, error_output) = check_case(&compiler, &cases[ind], timeout, snapshot_mode);
+                sender.send((ind, outcome, output, error_output, start.elapsed())).ok();
+            }
+        }));
+    }
+    drop(sender);
+
+    let mut results: Vec<Option<TestResult>> = (0..cases.len()).map(|_| None).collect();
+    for (ind, outcome, output, error_output, duration) in receiver {
+        results[ind] = Some(TestResult {
+            case: cases[ind].clone(),
+            outcome: outcome,
+            output: output,
+            error_output: error_output,
+            duration: duration,
+        });
+    }
+    for worker in workers {
+        worker.join().ok();
+    }
+    results.into_iter().flatten().collect()
+}
+
+/*
+Line diff of expected against actual using the longest common subsequence.
+Only changed lines are shown, prefixed with - (expected) or + (actual) and their line number.
+*/
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    const MAX_DIFF_LINES: usize = 20;
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut diff: Vec<String> = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("  -{:>4} {}", i + 1, old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("  +{:>4} {}", j + 1, new[j]));
+            j += 1;
+        }
+    }
+    if diff.is_empty() {
+        // only trailing newlines differ
+        diff.push("  (whitespace at end of file differs)".to_string());
+    }
+    if diff.len() > MAX_DIFF_LINES {
+        let hidden = diff.len() - MAX_DIFF_LINES;
+        diff.truncate(MAX_DIFF_LINES);
+        diff.push(format!("  ... {} more lines", hidden));
+    }
+    diff.join("\n")
+}
+
+pub fn run(input: &Path, mut writer: Box<dyn std::io::Write>, options: RunnerOptions, debug: bool) {
+    let cases = discover_tests(input);
+    if cases.is_empty() {
+        eprintln!("Error: no tests found under {}", input.display());
+        std::process::exit(1);
+    }
+    let jobs = options.jobs.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let compiler = match std::env::current_exe() {
+        Ok(compiler) => compiler,
+        Err(e) => {
+            eprintln!("Error: could not find the compiler executable: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let results = run_tests(&compiler, &cases, jobs, Duration::from_secs(options.timeout_secs), options.snapshot_mode);
+    if let Some(junit_path) = &options.junit {
+        write_report(junit_path, &junit_report(&results));
+    }
+    if let Some(json_path) = &options.json {
+        write_report(json_path, &json_report(&results));
+    }
+
+    let mut failed = 0;
+    for result in results.iter() {
+        let status = match &result.outcome {
+            Outcome::Pass => {
+                if debug {
+                    writeln!(writer, "Pass: {} ({} ms)", result.case.input.display(), result.duration.as_millis()).unwrap();
+                }
+                continue;
+            },
+            Outcome::Fail(reason) => format!("Fail: {}", reason),
+            Outcome::Timeout => format!("Timeout: exceeded {} s", options.timeout_secs),
+            Outcome::Panic(message) => format!("Panic: {}", message),
+        };
+        failed += 1;
+        writeln!(writer, "{} - {}", result.case.input.display(), status.trim_end()).unwrap();
+    }
+
+    // per stage summary, in pipeline order
+    for stage in [Stage::Scan, Stage::Parse, Stage::Inter] {
+        let stage_results: Vec<&TestResult> = results.iter().filter(|result| result.case.stage == stage).collect();
+        if stage_results.is_empty() {
+            continue;
+        }
+        let passed = stage_results.iter().filter(|result| matches!(result.outcome, Outcome::Pass)).count();
+        writeln!(writer, "Passed {} out of {} {} tests", passed, stage_results.len(), stage.dir_name()).unwrap();
+    }
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
@@ -0,0 +1,119 @@
+use super::super::scanner::scanner::scan_file;
+use super::super::parser::parser::parse_tokens;
+use super::super::parser::parser_printer::ParserPrinter;
+use super::super::semantics::semantics::check_program;
+use super::super::semantics::lints::LintConfig;
+use super::super::irs::cfg::cfg_creator::construct_program_graph;
+use super::runner::diff_lines;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/*
+Golden snapshots of every stage's output, stored next to the test input as NAME.STAGE.snap.
+The pipeline is run as far as the input allows: a stage that fails records its diagnostics and
+later stages get no snapshot. There is no assembly snapshot until the backend emits code.
+*/
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum SnapshotMode {
+    Off,
+    Check, // fail on any difference from the stored snapshots
+    Bless, // overwrite the stored snapshots with the current output
+}
+
+pub const SNAPSHOT_STAGES: [&str; 4] = ["scan", "parse", "inter", "ir"];
+
+pub fn snapshot_path(input: &Path, stage: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    input.with_file_name(format!("{}.{}.snap", stem, stage))
+}
+
+fn lines_to_string(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+// output of each stage reached by the input, in pipeline order
+pub fn stage_outputs(input: &Path) -> Vec<(&'static str, String)> {
+    let mut outputs: Vec<(&'static str, String)> = vec![];
+    let tokens = match scan_file(input) {
+        Ok(tokens) => tokens,
+        Err(errors) => {
+            outputs.push(("scan", lines_to_string(&errors)));
+            return outputs;
+        }
+    };
+    outputs.push(("scan", lines_to_string(&tokens)));
+
+    let ast = match parse_tokens(tokens) {
+        Ok(ast) => ast,
+        Err(errors) => {
+            outputs.push(("parse", lines_to_string(&errors)));
+            return outputs;
+        }
+    };
+    let mut printer = ParserPrinter::new();
+    ast.accept(&mut printer);
+    outputs.push(("parse", printer.output));
+
+    let analyzed_program = match check_program(ast, &LintConfig::default(), false) {
+        Ok((analyzed_program, _)) => analyzed_program,
+        Err(errors) => {
+            outputs.push(("inter", lines_to_string(&errors)));
+            return outputs;
+        }
+    };
+    outputs.push(("inter", String::new()));
+
+    outputs.push(("ir", construct_program_graph(&analyzed_program).dump()));
+    outputs
+}
+
+// compares (or with bless, rewrites) the snapshots of input, returning a description of the differences
+pub fn check_snapshots(input: &Path, mode: SnapshotMode) -> Result<(), String> {
+    let outputs = stage_outputs(input);
+    let mut differences: Vec<String> = vec![];
+    for stage in SNAPSHOT_STAGES {
+        let path = snapshot_path(input, stage);
+        let actual = outputs.iter().find(|(output_stage, _)| *output_stage == stage).map(|(_, output)| output);
+        let stored = std::fs::read_to_string(&path).ok();
+        if mode == SnapshotMode::Bless {
+            let written = match actual {
+                Some(actual) if stored.as_ref() != Some(actual) => std::fs::write(&path, actual),
+                None if stored.is_some() => std::fs::remove_file(&path),
+                _ => Ok(()),
+            };
+            if let Err(e) = written {
+                differences.push(format!("could not update {}: {}", path.display(), e));
+            }
+            continue;
+        }
+        match (actual, stored) {
+            (Some(actual), Some(stored)) if *actual != stored => {
+                differences.push(format!("{} snapshot differs from {}:\n{}", stage, path.display(), diff_lines(&stored, actual)));
+            },
+            (Some(_), None) => differences.push(format!("{} snapshot {} is missing, run with --bless to create it", stage, path.display())),
+            (None, Some(_)) => differences.push(format!("{} stage is no longer reached but {} exists", stage, path.display())),
+            _ => (),
+        }
+    }
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(differences.join("\n"))
+    }
+}
+
+// the snapshot target, checks (or with bless, updates) the snapshots of one decaf file
+pub fn snapshot(input: &Path, mut writer: Box<dyn std::io::Write>, mode: SnapshotMode) {
+    match check_snapshots(input, mode) {
+        Ok(()) => std::process::exit(0),
+        Err(differences) => {
+            writeln!(writer, "{}", differences).unwrap();
+            std::process::exit(1);
+        }
+    }
+}
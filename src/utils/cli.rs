@@ -7,7 +7,8 @@
  *
  * @author 6.1100 Staff, last updated January 2024
  */
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use super::super::semantics::lints::LintConfig;
 
 #[derive(Clone, clap::ValueEnum, Debug)]
 pub enum CompilerAction {
@@ -16,6 +17,12 @@ pub enum CompilerAction {
     Parse,
     Inter,
     Assembly,
+    Run,
+    Generate,
+    Reduce,
+    Test,
+    Symbols,
+    Snapshot,
 }
 
 #[derive(Clone, clap::ValueEnum, Debug, PartialEq, Eq, Hash)]
@@ -45,10 +52,64 @@ pub struct Args {
     #[arg(short, long, default_value_t = false)]
     pub debug: bool,
 
-    /// Decaf file
-    pub input: std::path::PathBuf,
+    /// Verify the IR and control flow graphs (always on in debug builds)
+    #[arg(long, default_value_t = false)]
+    pub verify_ir: bool,
+
+    /// Seed for the random program generator
+    #[arg(long, value_name = "seed")]
+    pub seed: Option<u64>,
+
+    /// Shell command deciding whether a reduced program is interesting (exit 0), {} is replaced with its path
+    #[arg(long, value_name = "command")]
+    pub interesting: Option<String>,
+
+    /// Number of tests run in parallel (defaults to the number of cores)
+    #[arg(long, value_name = "jobs")]
+    pub jobs: Option<usize>,
+
+    /// Seconds before a test is counted as timed out
+    #[arg(long, value_name = "seconds", default_value_t = 5)]
+    pub timeout: u64,
+
+    /// Compare every stage's output against the snapshots stored next to each test input
+    #[arg(long, default_value_t = false)]
+    pub snapshot: bool,
+
+    /// Update the stored snapshots instead of comparing against them
+    #[arg(long, default_value_t = false)]
+    pub bless: bool,
+
+    /// Write a JUnit XML report of the test results to
+    #[arg(long, value_name = "path")]
+    pub junit: Option<std::path::PathBuf>,
+
+    /// Write a JSON report of the test results to
+    #[arg(long, value_name = "path")]
+    pub json: Option<std::path::PathBuf>,
+
+    /// Enable a warning (a lint name or all), or turn warnings into errors with error or error=<lint>
+    #[arg(short = 'W', long = "warn", value_name = "lint")]
+    pub warn: Vec<String>,
+
+    /// Disable a warning (a lint name or all)
+    #[arg(short = 'A', long = "allow", value_name = "lint")]
+    pub allow: Vec<String>,
+
+    /// Decaf file, or the root directory of the test suites for the test target
+    pub input: Option<std::path::PathBuf>,
 }
 
 pub fn parse() -> Args {
-    Args::parse()
+    let args = Args::parse();
+    // every target except generate reads a decaf file
+    if args.input.is_none() && !matches!(args.target, CompilerAction::Generate) {
+        Args::command()
+            .error(clap::error::ErrorKind::MissingRequiredArgument, "the input decaf file is required for this target")
+            .exit();
+    }
+    if let Err(e) = LintConfig::from_flags(&args.warn, &args.allow) {
+        Args::command().error(clap::error::ErrorKind::InvalidValue, e).exit();
+    }
+    args
 }
@@ -0,0 +1,94 @@
+/*
+Spelling suggestions for diagnostics. A candidate is only offered when it is within a third of the
+name's length in edits (at least one), so short names are not matched against unrelated ones.
+*/
+
+// edit distance counting insertions, deletions, substitutions and swaps of adjacent characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        distances[i][0] = i;
+    }
+    for j in 0..=b.len() {
+        distances[0][j] = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            distances[i][j] = substitution.min(distances[i - 1][j] + 1).min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    return distances[a.len()][b.len()];
+}
+
+// closest candidates to name, all those tied at the smallest distance in alphabetical order
+pub fn closest_matches(name: &str, candidates: &[&str]) -> Vec<String> {
+    let mut best_distance = std::cmp::max(1, name.chars().count() / 3);
+    let mut matches: Vec<String> = vec![];
+    for candidate in candidates {
+        if *candidate == name {
+            continue;
+        }
+        let distance = edit_distance(name, candidate);
+        if distance < best_distance {
+            best_distance = distance;
+            matches.clear();
+        }
+        if distance == best_distance && !matches.iter().any(|m| m == candidate) {
+            matches.push(candidate.to_string());
+        }
+    }
+    matches.sort();
+    return matches;
+}
+
+// "did you mean a or b?" for the closest candidates, None if none are close enough
+pub fn did_you_mean(name: &str, candidates: &[&str]) -> Option<String> {
+    let matches = closest_matches(name, candidates);
+    if matches.is_empty() {
+        return None;
+    }
+    return Some(format!("did you mean {}?", matches.join(" or ")));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_matches, did_you_mean, edit_distance};
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("cout", "count"), 1);
+        assert_eq!(edit_distance("counts", "count"), 1);
+        assert_eq!(edit_distance("cound", "count"), 1);
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_as_one() {
+        assert_eq!(edit_distance("retrun", "return"), 1);
+        assert_eq!(edit_distance("whlie", "while"), 1);
+        assert_eq!(edit_distance("itn", "int"), 1);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+    }
+
+    #[test]
+    fn closest_matches_respects_the_length_threshold() {
+        // one edit is allowed for names shorter than six characters
+        assert!(closest_matches("cnt", &["count"]).is_empty());
+        assert_eq!(closest_matches("totl", &["total", "other"]), vec!["total"]);
+        // ties are all returned, alphabetically
+        assert_eq!(closest_matches("itn", &["int", "if", "in"]), vec!["in", "int"]);
+    }
+
+    #[test]
+    fn did_you_mean_formats_the_suggestion() {
+        assert_eq!(did_you_mean("retrun", &["return", "break"]), Some("did you mean return?".to_string()));
+        assert_eq!(did_you_mean("x", &["return"]), None);
+    }
+}
@@ -0,0 +1,127 @@
+use rust_compiler::semantics::lints::LintConfig;
+use rust_compiler::{check, compile, evaluate, parse, pretty_print, scan, symbols, CompileOptions, Diagnostics, Stage};
+
+/*
+The library entry points run on source held in memory. Each is checked on a program it accepts
+and on one that fails, which has to be reported by the right stage.
+*/
+
+const PROGRAM: &str = "import printf;
+int total;
+int add(int a, int b) {
+    return a + b;
+}
+void main() {
+    total = add(2, 3);
+    printf(\"%d\\n\", total);
+}
+";
+
+const SCAN_ERROR: &str = "void main() { int x; x = 1 # 2; }";
+const PARSE_ERROR: &str = "void main() { int x x = 1; }";
+const SEMANTIC_ERROR: &str = "void main() { x = 1; }";
+// legal, but returns nothing when x is not positive
+const MISSING_RETURN: &str = "int f(int x) {\n if (x > 0) {\n return x;\n }\n}\nvoid main() { f(1); }";
+
+fn expect_error<T>(result: Result<T, Diagnostics>) -> Diagnostics {
+    match result {
+        Ok(_) => panic!("expected the source to be rejected"),
+        Err(diagnostics) => diagnostics,
+    }
+}
+
+#[test]
+fn scan_returns_tokens_or_scanner_errors() {
+    let tokens = scan("int x;").expect("source should scan");
+    assert_eq!(tokens, vec!["1 int", "1 IDENTIFIER x", "1 ;"]);
+
+    let diagnostics = expect_error(scan(SCAN_ERROR));
+    assert_eq!(diagnostics.stage, Stage::Scan);
+    assert_eq!(diagnostics.messages, vec!["Scanner: Line 1 - Error: invalid symbol: #"]);
+}
+
+#[test]
+fn parse_reports_the_first_failing_stage() {
+    let ast = parse(PROGRAM).expect("source should parse");
+    assert_eq!((ast.imports.len(), ast.fields.len(), ast.methods.len()), (1, 1, 2));
+    assert!(pretty_print(PROGRAM).expect("source should parse").contains("add"));
+
+    let diagnostics = expect_error(parse(PARSE_ERROR));
+    assert_eq!(diagnostics.stage, Stage::Parse);
+    assert_eq!(diagnostics.messages, vec!["Line: 1 - Expected token: ;, got: x"]);
+    // a scanner error stops the pipeline before the parser
+    assert_eq!(expect_error(parse(SCAN_ERROR)).stage, Stage::Scan);
+    assert_eq!(expect_error(pretty_print(PARSE_ERROR)).stage, Stage::Parse);
+}
+
+#[test]
+fn check_type_checks_at_the_default_lint_levels() {
+    let program = check(PROGRAM).expect("source should pass semantic checks");
+    assert!(program.lookup_global("add").is_some());
+    // lints only warn by default
+    assert!(check(MISSING_RETURN).is_ok());
+
+    let diagnostics = expect_error(check(SEMANTIC_ERROR));
+    assert_eq!(diagnostics.stage, Stage::Semantics);
+    assert_eq!(diagnostics.messages.len(), 1);
+    assert!(diagnostics.messages[0].starts_with("Error: Identifier x is used before it is declared."));
+    assert_eq!(expect_error(check(PARSE_ERROR)).stage, Stage::Parse);
+}
+
+#[test]
+fn symbols_resolve_every_identifier() {
+    let occurrences = symbols(PROGRAM).expect("source should pass semantic checks");
+    let total: Vec<(usize, i32)> = occurrences.iter()
+        .filter(|occurrence| occurrence.name == "total")
+        .map(|occurrence| (occurrence.line_num, occurrence.status))
+        .collect();
+    assert_eq!(total, vec![(2, 0), (7, 2), (8, 1)]);
+
+    assert_eq!(expect_error(symbols(SEMANTIC_ERROR)).stage, Stage::Semantics);
+}
+
+#[test]
+fn evaluate_runs_the_program() {
+    assert_eq!(evaluate(PROGRAM).expect("source should pass semantic checks"), ("5\n".to_string(), 0));
+    // runtime errors are an exit code, not diagnostics
+    assert_eq!(evaluate(MISSING_RETURN).expect("source should pass semantic checks").1, 0);
+    assert_eq!(evaluate("int f() { }\nvoid main() { f(); }").expect("source should pass semantic checks").1, -2);
+
+    assert_eq!(expect_error(evaluate(SEMANTIC_ERROR)).stage, Stage::Semantics);
+}
+
+#[test]
+fn compile_defaults_verify_the_ir() {
+    let options = CompileOptions::default();
+    assert!(options.verify_ir);
+    assert!(!options.debug);
+
+    let artifacts = compile(PROGRAM, &options).expect("source should compile");
+    assert_eq!(artifacts.tokens, scan(PROGRAM).expect("source should scan"));
+    assert!(artifacts.warnings.is_empty());
+    assert!(artifacts.ir_warnings.is_empty());
+    let mut methods: Vec<&String> = artifacts.program_graph.method_graphs.keys().collect();
+    methods.sort();
+    assert_eq!(methods, vec!["add", "main"]);
+
+    let artifacts = compile(MISSING_RETURN, &options).expect("a missing return only warns");
+    assert_eq!(artifacts.warnings.len(), 1);
+    assert!(artifacts.warnings[0].ends_with("[missing-return]"));
+}
+
+#[test]
+fn compile_reports_the_failing_stage() {
+    let options = CompileOptions::default();
+    assert_eq!(expect_error(compile(SCAN_ERROR, &options)).stage, Stage::Scan);
+    assert_eq!(expect_error(compile(PARSE_ERROR, &options)).stage, Stage::Parse);
+    assert_eq!(expect_error(compile(SEMANTIC_ERROR, &options)).stage, Stage::Semantics);
+
+    // denied lints fail the semantics stage
+    let options = CompileOptions {
+        lints: LintConfig::from_flags(&["error=missing-return".to_string()], &[]).expect("flags should be valid"),
+        ..CompileOptions::default()
+    };
+    let diagnostics = expect_error(compile(MISSING_RETURN, &options));
+    assert_eq!(diagnostics.stage, Stage::Semantics);
+    assert!(diagnostics.messages[0].starts_with("Line: 1 - Error - "));
+}
@@ -1,49 +1,21 @@
 #!/bin/bash
 
-SCANNER="target/debug/rust-compiler"
+COMPILER="target/debug/rust-compiler"
 PUBLIC_ROOT="tests24/public-tests-main/scanner"
 PRIVATE_ROOT="tests24/private-tests-main/scanner"
 TIMEOUT=5
-COUNT=0
-TOTAL=0
 
 cargo build
 
-run_tests () {
-    COUNT=0
-    TOTAL=0
-    for filename in "$1"/input/*.dcf; do
-        touch tests24/tmp.out
-        echo "Testing: $filename"
-        outname="$(basename "${filename%.dcf}.out")"
-        timeout $TIMEOUT $SCANNER --target scan $filename --output tmp.out
-        if [[ $filename == *invalid* ]]; then
-            if grep -q "Error" tmp.out; then
-                echo "Pass (invalid): $(basename "$filename")"
-                COUNT=$((COUNT+1))
-            else
-                echo "Fail (invalid): $(basename "$filename")"
-            fi
-        else
-            if diff tmp.out $1/output/$outname; then
-                echo "Pass: $(basename $filename)"
-                COUNT=$((COUNT+1))
-            else
-                echo "Fail: $(basename $filename)"
-            fi
-        fi
-        TOTAL=$((TOTAL+1))
-        rm -f tests24/tmp.out
-    done
-    echo "Passed $COUNT out of $TOTAL scanner tests"
-}
-
+# the test target discovers and runs the suite in-process; extra arguments are passed through,
+# e.g. -d to list passing tests or --snapshot and --bless for the per-stage golden outputs
 if [[ $1 == "public" ]]; then
     echo "Running public tests"
-    run_tests $PUBLIC_ROOT
+    $COMPILER --target test --timeout $TIMEOUT $PUBLIC_ROOT "${@:2}"
 else
     echo "Running public tests"
-    run_tests $PUBLIC_ROOT
+    $COMPILER --target test --timeout $TIMEOUT $PUBLIC_ROOT "${@:2}"
+    PUBLIC_CODE=$?
     echo "Running private tests"
-    run_tests $PRIVATE_ROOT 
+    $COMPILER --target test --timeout $TIMEOUT $PRIVATE_ROOT "${@:2}" && exit $PUBLIC_CODE
 fi
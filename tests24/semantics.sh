@@ -1,58 +1,21 @@
 #!/bin/bash
 
-INTERPRETER="target/debug/rust-compiler"
+COMPILER="target/debug/rust-compiler"
 PUBLIC_ROOT="tests24/public-tests-main/semantics"
 PRIVATE_ROOT="tests24/private-tests-main/semantics"
 TIMEOUT=5
-COUNT=0
-TOTAL=0
 
 cargo build
 
-run_tests () {
-    COUNT=0
-    TOTAL=0
-    for filename in "$1"/illegal/*.dcf; do
-        touch tests24/tmp.out
-        echo "Testing: $filename"
-        timeout $TIMEOUT $INTERPRETER --target inter $filename --output tests24/tmp.out
-        CODE=$?
-        if [ $CODE -ne 1 ]; then
-            echo "Fail (illegal): $(basename "$filename")"
-            rm -f tests24/tmp.out
-        else 
-            echo "Pass (illegal): $(basename "$filename")"
-            COUNT=$((COUNT+1))
-        fi
-        TOTAL=$((TOTAL+1))
-        # rm -f tests/tmp.out
-    done
-
-    for filename in "$1"/legal/*.dcf; do
-        touch tests24/tmp.out
-        echo "Testing: $filename"
-        timeout $TIMEOUT $INTERPRETER --target inter $filename --output tests24/tmp.out
-        CODE=$?
-        if [ $CODE -ne 0 ]; then
-            echo "Fail (legal): $(basename "$filename")"
-            rm -f tests24/tmp.out
-        else 
-            echo "Pass (legal): $(basename "$filename")"
-            COUNT=$((COUNT+1))
-        fi
-        TOTAL=$((TOTAL+1))
-        # rm -f tests/tmp.out
-    done    
-
-    echo "Passed $COUNT out of $TOTAL semantics tests"
-}
-
+# the test target discovers and runs the suite in-process; extra arguments are passed through,
+# e.g. -d to list passing tests or --snapshot and --bless for the per-stage golden outputs
 if [[ $1 == "public" ]]; then
     echo "Running public tests"
-    run_tests $PUBLIC_ROOT
+    $COMPILER --target test --timeout $TIMEOUT $PUBLIC_ROOT "${@:2}"
 else
     echo "Running public tests"
-    run_tests $PUBLIC_ROOT
+    $COMPILER --target test --timeout $TIMEOUT $PUBLIC_ROOT "${@:2}"
+    PUBLIC_CODE=$?
     echo "Running private tests"
-    run_tests $PRIVATE_ROOT 
+    $COMPILER --target test --timeout $TIMEOUT $PRIVATE_ROOT "${@:2}" && exit $PUBLIC_CODE
 fi